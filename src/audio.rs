@@ -1,9 +1,26 @@
 use bevy::{
     asset::{LoadedFolder, RecursiveDependencyLoadState},
+    ecs::system::SystemParam,
     prelude::*,
 };
 use bevy_kira_audio::*;
 
+use crate::{camera::MainCamera, game::PlayerHitEvent};
+
+use self::{
+    adaptive_music::AdaptiveMusicPlugin,
+    mixer::MixerPlugin,
+    music_director::{handle_play_music_events, handle_stop_music_events, MusicDirectorPlugin},
+};
+pub use self::{
+    adaptive_music::{MusicIntensity, MusicLayer},
+    mixer::{AudioBus, AudioBusChannels, AudioMixer, DuckMusicEvent},
+};
+
+mod adaptive_music;
+mod mixer;
+mod music_director;
+
 pub const ASSET_FOLDER_MUSIC: &str = "music";
 pub const ASSET_FOLDER_SFX: &str = "sfx";
 
@@ -20,7 +37,10 @@ impl Plugin for AudioPlugin {
         app.init_resource::<SoundEffectHandles>();
         app.add_audio_channel::<BgmChannel>();
         app.add_audio_channel::<DragonBreathChannel>();
+        app.add_audio_channel::<ChargeBreathChannel>();
+        app.add_plugins((AdaptiveMusicPlugin, MixerPlugin, MusicDirectorPlugin));
         app.add_systems(Startup, (load_music_files, load_sound_effect_files));
+        app.add_systems(Update, (play_button_press_sounds, play_player_hit_sounds));
         app.add_systems(
             Update,
             (
@@ -48,6 +68,76 @@ pub struct BgmChannel;
 #[derive(Resource)]
 pub struct DragonBreathChannel;
 
+/// Loops while a breath is charging, apart from the regular breath so either can stop without
+/// cutting the other off.
+#[derive(Resource)]
+pub struct ChargeBreathChannel;
+
+/// The sounds of the dragon's breath, which loop on their own channels while it's held or
+/// charged.
+#[derive(SystemParam)]
+pub struct BreathAudio<'w> {
+    asset_server: Res<'w, AssetServer>,
+    dragon_breath_audio_channel: Res<'w, AudioChannel<DragonBreathChannel>>,
+    charge_breath_audio_channel: Res<'w, AudioChannel<ChargeBreathChannel>>,
+    play_sound_effect_event_writer: EventWriter<'w, PlaySoundEffectEvent>,
+}
+
+impl BreathAudio<'_> {
+    /// Plays the start of the breath followed by its loop.
+    pub fn start(&self) {
+        self.dragon_breath_audio_channel
+            .play(self.sound("breathstart.ogg"));
+        self.dragon_breath_audio_channel
+            .play(self.sound("breathloop.ogg"))
+            .looped();
+    }
+
+    /// Stops the loop and plays the end of the breath.
+    pub fn end(&mut self) {
+        self.play_sound_effect_event_writer
+            .send(PlaySoundEffectEvent::new_with_defaults("breathend.ogg"));
+        self.stop();
+    }
+
+    /// Stops the loop without the end of the breath.
+    pub fn stop(&self) {
+        self.dragon_breath_audio_channel.stop();
+    }
+
+    /// Plays the charging loop, which rises in pitch with the charge.
+    pub fn start_charge(&self) {
+        self.charge_breath_audio_channel
+            .play(self.sound("breathloop.ogg"))
+            .looped();
+    }
+
+    /// Sets how full the charge sounds, from 0 to 1.
+    pub fn set_charge(&self, charge: f32) {
+        self.charge_breath_audio_channel
+            .set_playback_rate(1. + charge as f64);
+    }
+
+    /// Stops the charging loop and plays the end of the breath.
+    pub fn end_charge(&mut self) {
+        self.play_sound_effect_event_writer
+            .send(PlaySoundEffectEvent::new_with_defaults("breathend.ogg"));
+        self.stop_charge();
+    }
+
+    /// Stops the charging loop without the end of the breath.
+    pub fn stop_charge(&self) {
+        self.charge_breath_audio_channel.stop();
+        self.charge_breath_audio_channel.set_playback_rate(1.);
+    }
+
+    fn sound(&self, file_name: &str) -> Handle<AudioSource> {
+        self.asset_server
+            .get_handle(format_sfx_file_name(file_name))
+            .unwrap_or_default()
+    }
+}
+
 #[derive(PartialEq)]
 enum AudioLoadState {
     NotLoaded,
@@ -101,6 +191,12 @@ struct MusicHandles(
     #[cfg(target_family = "wasm")] Vec<Handle<AudioSource>>,
 );
 
+/// Keeps the optional music stems loaded on the web, where the music folder can't be loaded as
+/// a whole.
+#[cfg(target_family = "wasm")]
+#[derive(Resource, Deref)]
+struct MusicStemHandles(Vec<Handle<AudioSource>>);
+
 #[derive(Resource, Default, Deref, DerefMut)]
 struct SoundEffectHandles(
     #[cfg(not(target_family = "wasm"))] Handle<LoadedFolder>,
@@ -136,6 +232,7 @@ pub struct PlayMusicEvent {
     file_name: String,
     settings: Option<PlaybackSettings>,
     spatial_transform: Option<Transform>,
+    layers: Vec<MusicLayer>,
 }
 
 impl PlayMusicEvent {
@@ -149,6 +246,7 @@ impl PlayMusicEvent {
             file_name,
             settings,
             spatial_transform,
+            layers: Vec::new(),
         }
     }
 
@@ -158,8 +256,15 @@ impl PlayMusicEvent {
             file_name,
             settings: None,
             spatial_transform: None,
+            layers: Vec::new(),
         }
     }
+
+    /// Plays the theme's stems for `layers` along with it, driven by [`MusicIntensity`].
+    pub fn with_layers(mut self, layers: impl IntoIterator<Item = MusicLayer>) -> Self {
+        self.layers = layers.into_iter().collect();
+        self
+    }
 }
 
 #[derive(Event)]
@@ -170,6 +275,7 @@ pub struct PlaySoundEffectEvent {
     pub file_name: String,
     pub settings: Option<PlaybackSettings>,
     pub spatial_transform: Option<Transform>,
+    pub bus: AudioBus,
 }
 
 impl PlaySoundEffectEvent {
@@ -183,6 +289,7 @@ impl PlaySoundEffectEvent {
             file_name,
             settings,
             spatial_transform,
+            bus: AudioBus::default(),
         }
     }
 
@@ -192,34 +299,50 @@ impl PlaySoundEffectEvent {
             file_name,
             settings: None,
             spatial_transform: None,
+            bus: AudioBus::default(),
         }
     }
-}
 
-#[derive(Component)]
-pub struct BackgroundMusic;
+    /// Plays the sound on `bus` instead of the sound effects bus.
+    pub fn with_bus(mut self, bus: AudioBus) -> Self {
+        self.bus = bus;
+        self
+    }
+}
 
 #[derive(Component)]
 pub struct SoundEffect;
 
-fn handle_play_music_events(
+fn handle_play_sound_effect_events(
     asset_server: Res<AssetServer>,
-    bgm_audio_channel: Res<AudioChannel<BgmChannel>>,
-    mut event_reader: EventReader<PlayMusicEvent>,
+    audio_bus_channels: AudioBusChannels,
+    mut event_reader: EventReader<PlaySoundEffectEvent>,
+    camera_query: Query<&GlobalTransform, With<MainCamera>>,
 ) {
+    let default_settings = PlaybackSettings::default();
+    let listener_position = camera_query
+        .get_single()
+        .ok()
+        .map(|camera_transform| camera_transform.translation().truncate());
+
     for event in event_reader.read() {
-        let PlayMusicEvent {
+        let PlaySoundEffectEvent {
             file_name,
             settings,
             spatial_transform,
+            bus,
         } = event;
 
-        let path = format_music_file_name(file_name);
+        let path = format_sfx_file_name(file_name);
         let mut play_audio_command =
-            bgm_audio_channel.play(asset_server.get_handle(path).unwrap_or_default());
-        let Some(settings) = settings else {
-            return;
-        };
+            audio_bus_channels.play(*bus, asset_server.get_handle(path).unwrap_or_default());
+        let settings = settings.as_ref().unwrap_or(&default_settings);
+        let (panning, attenuation) = spatial_transform.zip(listener_position).map_or(
+            (settings.panning, 1.),
+            |(spatial_transform, listener_position)| {
+                spatialize(spatial_transform.translation.truncate(), listener_position)
+            },
+        );
 
         if settings.reverse {
             play_audio_command.reverse();
@@ -235,60 +358,53 @@ fn handle_play_music_events(
         }
 
         play_audio_command
-            .with_panning(settings.panning)
+            .with_panning(panning)
             .with_playback_rate(settings.playback_rate)
-            .with_volume(settings.volume);
+            .with_volume(settings.volume * attenuation);
     }
 }
 
-fn handle_stop_music_events(
-    mut commands: Commands,
-    mut event_reader: EventReader<StopMusicEvent>,
-    query: Query<Entity, With<BackgroundMusic>>,
+/// Clicks whenever a button is pressed, on the interface bus so it is heard in menus too.
+fn play_button_press_sounds(
+    mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+    query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
 ) {
-    if !event_reader.is_empty() {
-        let entity = query.single();
-        commands.entity(entity).despawn_recursive();
+    if query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        play_sound_effect_event_writer
+            .send(PlaySoundEffectEvent::new_with_defaults("ui_click.ogg").with_bus(AudioBus::Ui));
     }
-    event_reader.clear();
 }
 
-fn handle_play_sound_effect_events(
-    asset_server: Res<AssetServer>,
-    audio: Res<Audio>,
-    mut event_reader: EventReader<PlaySoundEffectEvent>,
+fn play_player_hit_sounds(
+    mut player_hit_event_reader: EventReader<PlayerHitEvent>,
+    mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
 ) {
-    for event in event_reader.read() {
-        let PlaySoundEffectEvent {
-            file_name,
-            settings,
-            spatial_transform,
-        } = event;
+    for event in player_hit_event_reader.read() {
+        play_sound_effect_event_writer.send(PlaySoundEffectEvent::new(
+            "hit.ogg",
+            None,
+            Some(Transform::from_translation(event.position.extend(0.))),
+        ));
+    }
+}
 
-        let path = format_sfx_file_name(file_name);
-        let mut play_audio_command = audio.play(asset_server.get_handle(path).unwrap_or_default());
-        let Some(settings) = settings else {
-            return;
-        };
+/// Panning and volume of a sound made at `emitter_position`, as heard by the camera at
+/// `listener_position`. Sounds pan towards the side they come from and fade out with distance.
+fn spatialize(emitter_position: Vec2, listener_position: Vec2) -> (f64, f64) {
+    const FULL_PAN_DISTANCE: f32 = 640.;
+    const FULL_VOLUME_DISTANCE: f32 = 320.;
+    const SILENT_DISTANCE: f32 = 1600.;
 
-        if settings.reverse {
-            play_audio_command.reverse();
-        }
-        if let Some(fade_in) = &settings.fade_in {
-            play_audio_command.fade_in(fade_in.clone());
-        }
-        if let Some(loop_from) = settings.loop_from {
-            play_audio_command.loop_from(loop_from);
-        }
-        if let Some(loop_until) = settings.loop_until {
-            play_audio_command.loop_until(loop_until);
-        }
+    let offset = emitter_position - listener_position;
+    let panning = 0.5 + 0.5 * (offset.x / FULL_PAN_DISTANCE).clamp(-1., 1.);
+    let attenuation = 1.
+        - ((offset.length() - FULL_VOLUME_DISTANCE) / (SILENT_DISTANCE - FULL_VOLUME_DISTANCE))
+            .clamp(0., 1.);
 
-        play_audio_command
-            .with_panning(settings.panning)
-            .with_playback_rate(settings.playback_rate)
-            .with_volume(settings.volume);
-    }
+    (panning as f64, (attenuation * attenuation) as f64)
 }
 
 fn load_music_files(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -305,6 +421,20 @@ fn load_music_files(mut commands: Commands, asset_server: Res<AssetServer>) {
                 format_music_file_name("theme2.ogg"),
                 format_music_file_name("theme3.ogg"),
             ];
+            // Stems aren't waited on, so a missing one can't hold up the game.
+            let music_stem_list = [
+                format_music_file_name("theme2_brass.ogg"),
+                format_music_file_name("theme2_climax.ogg"),
+                format_music_file_name("theme2_percussion.ogg"),
+            ];
+
+            commands.insert_resource(MusicStemHandles(
+                music_stem_list
+                    .iter()
+                    .map(|path| asset_server.load::<AudioSource>(path))
+                    .collect(),
+            ));
+
             asset_music_list
                 .iter()
                 .map(|path| asset_server.load::<AudioSource>(path))
@@ -325,9 +455,16 @@ fn load_sound_effect_files(mut commands: Commands, asset_server: Res<AssetServer
         #[cfg(target_family = "wasm")]
         {
             let asset_sfx_list = [
+                format_sfx_file_name("ambience.ogg"),
+                format_sfx_file_name("bolt.ogg"),
                 format_sfx_file_name("breathend.ogg"),
                 format_sfx_file_name("breathloop.ogg"),
                 format_sfx_file_name("breathstart.ogg"),
+                format_sfx_file_name("explosion.ogg"),
+                format_sfx_file_name("hit.ogg"),
+                format_sfx_file_name("throw.ogg"),
+                format_sfx_file_name("ui_click.ogg"),
+                format_sfx_file_name("volley.ogg"),
             ];
             asset_sfx_list
                 .iter()
@@ -404,3 +541,39 @@ fn format_music_file_name(file_name: &str) -> String {
 fn format_sfx_file_name(file_name: &str) -> String {
     format!("{ASSET_FOLDER_SFX}/{file_name}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spatialize_pans_towards_the_emitter() {
+        let listener_position = Vec2::new(100., 100.);
+
+        let (centered, _) = spatialize(listener_position, listener_position);
+        let (left, _) = spatialize(listener_position - Vec2::X * 320., listener_position);
+        let (right, _) = spatialize(listener_position + Vec2::X * 320., listener_position);
+        let (far_left, _) = spatialize(listener_position - Vec2::X * 1000., listener_position);
+        let (above, _) = spatialize(listener_position + Vec2::Y * 320., listener_position);
+
+        assert_eq!(centered, 0.5);
+        assert!(left < 0.5);
+        assert!(right > 0.5);
+        assert_eq!(left + right, 1.);
+        assert_eq!(far_left, 0.);
+        assert_eq!(above, 0.5);
+    }
+
+    #[test]
+    fn spatialize_fades_out_between_full_volume_and_silent_distances() {
+        let attenuation = |distance: f32| spatialize(Vec2::new(distance, 0.), Vec2::ZERO).1;
+
+        assert_eq!(attenuation(0.), 1.);
+        assert_eq!(attenuation(320.), 1.);
+        assert!(attenuation(640.) < 1.);
+        assert!(attenuation(1200.) < attenuation(640.));
+        assert!(attenuation(1200.) > 0.);
+        assert_eq!(attenuation(1600.), 0.);
+        assert_eq!(attenuation(3000.), 0.);
+    }
+}
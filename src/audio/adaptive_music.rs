@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy_kira_audio::AudioApp;
+
+use super::AudioMixer;
+
+pub(super) struct AdaptiveMusicPlugin;
+
+impl Plugin for AdaptiveMusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<PercussionLayerChannel>();
+        app.add_audio_channel::<BrassLayerChannel>();
+        app.add_audio_channel::<ClimaxLayerChannel>();
+        app.init_resource::<MusicIntensity>();
+        app.add_systems(Update, update_music_layer_gains);
+    }
+}
+
+#[derive(Resource)]
+pub struct PercussionLayerChannel;
+
+#[derive(Resource)]
+pub struct BrassLayerChannel;
+
+#[derive(Resource)]
+pub struct ClimaxLayerChannel;
+
+/// How heated the game is, from 0 for calm exploration to 1 for an all-out fight. The game
+/// keeps it up to date and the music layers follow it.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct MusicIntensity(pub f32);
+
+/// A stem played in sync with a theme, on its own channel, that swells in as the intensity
+/// rises. A layer's file is named after its theme, like `theme2_percussion.ogg`. Stems are
+/// optional: a theme whose stem is missing or still loading plays without that layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MusicLayer {
+    Percussion,
+    Brass,
+    /// Only heard at the very peak of a fight. There are no bosses, so this is the closest the
+    /// music gets to a boss theme.
+    Climax,
+}
+
+impl MusicLayer {
+    pub const ALL: [Self; 3] = [Self::Percussion, Self::Brass, Self::Climax];
+
+    pub(super) fn file_name(&self, theme_file_name: &str) -> String {
+        let suffix = match self {
+            Self::Percussion => "percussion",
+            Self::Brass => "brass",
+            Self::Climax => "climax",
+        };
+        let (stem, extension) = theme_file_name
+            .rsplit_once('.')
+            .unwrap_or((theme_file_name, "ogg"));
+
+        format!("{stem}_{suffix}.{extension}")
+    }
+
+    /// Intensities between which the layer fades from silent to full volume.
+    fn intensity_range(&self) -> (f32, f32) {
+        match self {
+            Self::Percussion => (0.2, 0.45),
+            Self::Brass => (0.45, 0.75),
+            Self::Climax => (0.9, 1.),
+        }
+    }
+
+    fn gain(&self, intensity: f32) -> f64 {
+        let (silent, full) = self.intensity_range();
+
+        ((intensity - silent) / (full - silent)).clamp(0., 1.) as f64
+    }
+}
+
+fn update_music_layer_gains(
+    mut audio_mixer: ResMut<AudioMixer>,
+    music_intensity: Res<MusicIntensity>,
+    time: Res<Time<Real>>,
+) {
+    // Layers ease towards the intensity so a single kill doesn't make the music stutter.
+    const GAIN_CHANGE_PER_SECOND: f64 = 0.5;
+
+    let max_change = GAIN_CHANGE_PER_SECOND * time.delta_seconds_f64();
+
+    for layer in MusicLayer::ALL {
+        let gain = audio_mixer.layer_gain(layer);
+        let target_gain = layer.gain(music_intensity.0);
+
+        if gain != target_gain {
+            audio_mixer.set_layer_gain(
+                layer,
+                gain + (target_gain - gain).clamp(-max_change, max_change),
+            );
+        }
+    }
+}
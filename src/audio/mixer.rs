@@ -0,0 +1,283 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_kira_audio::{Audio, AudioApp, AudioChannel, AudioControl, AudioSource, PlayAudioCommand};
+
+use super::{
+    adaptive_music::{BrassLayerChannel, ClimaxLayerChannel, PercussionLayerChannel},
+    BgmChannel, ChargeBreathChannel, DragonBreathChannel, MusicLayer,
+};
+
+pub(super) struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<SfxChannel>();
+        app.add_audio_channel::<UiChannel>();
+        app.add_audio_channel::<AmbienceChannel>();
+        app.add_event::<DuckMusicEvent>();
+        app.init_resource::<AudioMixer>();
+        app.add_systems(Update, (handle_duck_music_events, update_mixer).chain());
+    }
+}
+
+#[derive(Resource)]
+pub struct SfxChannel;
+
+#[derive(Resource)]
+pub struct UiChannel;
+
+#[derive(Resource)]
+pub struct AmbienceChannel;
+
+/// A group of sounds sharing a volume. Every bus plays through [`AudioBus::Master`], and
+/// sounds sent straight to the master bus ignore every other volume.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AudioBus {
+    Master,
+    Music,
+    #[default]
+    Sfx,
+    Ui,
+    Ambience,
+}
+
+impl AudioBus {
+    pub const ALL: [Self; 5] = [
+        Self::Master,
+        Self::Music,
+        Self::Sfx,
+        Self::Ui,
+        Self::Ambience,
+    ];
+}
+
+/// The volume of every bus, how far the music is currently ducked and how loud each music
+/// layer is on top of the music bus.
+#[derive(Resource)]
+pub struct AudioMixer {
+    volumes: HashMap<AudioBus, f64>,
+    ducking: Option<Ducking>,
+    layer_gains: HashMap<MusicLayer, f64>,
+    /// Output volumes last sent to the channels, so they are only updated on change.
+    applied: HashMap<AudioBus, f64>,
+    applied_layers: HashMap<MusicLayer, f64>,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self {
+            volumes: AudioBus::ALL.into_iter().map(|bus| (bus, 1.)).collect(),
+            ducking: None,
+            layer_gains: HashMap::default(),
+            applied: HashMap::default(),
+            applied_layers: HashMap::default(),
+        }
+    }
+}
+
+impl AudioMixer {
+    pub fn volume(&self, bus: AudioBus) -> f64 {
+        self.volumes.get(&bus).copied().unwrap_or(1.)
+    }
+
+    pub fn set_volume(&mut self, bus: AudioBus, volume: f64) {
+        self.volumes.insert(bus, volume.clamp(0., 1.));
+    }
+
+    /// Volume of a music layer relative to the music bus. Layers are silent until raised.
+    pub fn layer_gain(&self, layer: MusicLayer) -> f64 {
+        self.layer_gains.get(&layer).copied().unwrap_or(0.)
+    }
+
+    pub fn set_layer_gain(&mut self, layer: MusicLayer, gain: f64) {
+        self.layer_gains.insert(layer, gain.clamp(0., 1.));
+    }
+
+    /// What the bus' channels actually play at, after the master volume and ducking.
+    fn output_volume(&self, bus: AudioBus) -> f64 {
+        let master_volume = self.volume(AudioBus::Master);
+
+        match bus {
+            AudioBus::Master => master_volume,
+            AudioBus::Music => {
+                let ducking = self.ducking.as_ref().map_or(1., |ducking| ducking.level);
+
+                master_volume * self.volume(bus) * ducking
+            }
+            _ => master_volume * self.volume(bus),
+        }
+    }
+}
+
+/// Music lowered for a moment so a loud sound can cut through.
+struct Ducking {
+    level: f64,
+    hold: Timer,
+}
+
+/// Lowers the music to `level` of its volume for `seconds`, after which it swells back.
+#[derive(Event)]
+pub struct DuckMusicEvent {
+    level: f64,
+    seconds: f32,
+}
+
+impl DuckMusicEvent {
+    pub fn new(level: f64, seconds: f32) -> Self {
+        Self { level, seconds }
+    }
+}
+
+/// The channels behind every bus.
+#[derive(SystemParam)]
+pub struct AudioBusChannels<'w> {
+    master: Res<'w, Audio>,
+    music: Res<'w, AudioChannel<BgmChannel>>,
+    sfx: Res<'w, AudioChannel<SfxChannel>>,
+    dragon_breath: Res<'w, AudioChannel<DragonBreathChannel>>,
+    charge_breath: Res<'w, AudioChannel<ChargeBreathChannel>>,
+    ui: Res<'w, AudioChannel<UiChannel>>,
+    ambience: Res<'w, AudioChannel<AmbienceChannel>>,
+    percussion_layer: Res<'w, AudioChannel<PercussionLayerChannel>>,
+    brass_layer: Res<'w, AudioChannel<BrassLayerChannel>>,
+    climax_layer: Res<'w, AudioChannel<ClimaxLayerChannel>>,
+}
+
+impl AudioBusChannels<'_> {
+    pub fn play(&self, bus: AudioBus, audio_source: Handle<AudioSource>) -> PlayAudioCommand<'_> {
+        match bus {
+            AudioBus::Master => self.master.play(audio_source),
+            AudioBus::Music => self.music.play(audio_source),
+            AudioBus::Sfx => self.sfx.play(audio_source),
+            AudioBus::Ui => self.ui.play(audio_source),
+            AudioBus::Ambience => self.ambience.play(audio_source),
+        }
+    }
+
+    pub fn play_layer(
+        &self,
+        layer: MusicLayer,
+        audio_source: Handle<AudioSource>,
+    ) -> PlayAudioCommand<'_> {
+        match layer {
+            MusicLayer::Percussion => self.percussion_layer.play(audio_source),
+            MusicLayer::Brass => self.brass_layer.play(audio_source),
+            MusicLayer::Climax => self.climax_layer.play(audio_source),
+        }
+    }
+
+    /// Pauses everything but the interface sounds, which keep playing in menus.
+    pub fn pause_game(&self) {
+        self.master.pause();
+        self.music.pause();
+        self.sfx.pause();
+        self.ambience.pause();
+        self.percussion_layer.pause();
+        self.brass_layer.pause();
+        self.climax_layer.pause();
+    }
+
+    pub fn resume_game(&self) {
+        self.master.resume();
+        self.music.resume();
+        self.sfx.resume();
+        self.ambience.resume();
+        self.percussion_layer.resume();
+        self.brass_layer.resume();
+        self.climax_layer.resume();
+    }
+
+    pub fn stop_ambience(&self) {
+        self.ambience.stop();
+    }
+
+    fn set_volume(&self, bus: AudioBus, volume: f64) {
+        match bus {
+            AudioBus::Master => {
+                self.master.set_volume(volume);
+            }
+            AudioBus::Music => {
+                self.music.set_volume(volume);
+            }
+            AudioBus::Sfx => {
+                self.sfx.set_volume(volume);
+                self.dragon_breath.set_volume(volume);
+                self.charge_breath.set_volume(volume);
+            }
+            AudioBus::Ui => {
+                self.ui.set_volume(volume);
+            }
+            AudioBus::Ambience => {
+                self.ambience.set_volume(volume);
+            }
+        }
+    }
+
+    fn set_layer_volume(&self, layer: MusicLayer, volume: f64) {
+        match layer {
+            MusicLayer::Percussion => {
+                self.percussion_layer.set_volume(volume);
+            }
+            MusicLayer::Brass => {
+                self.brass_layer.set_volume(volume);
+            }
+            MusicLayer::Climax => {
+                self.climax_layer.set_volume(volume);
+            }
+        }
+    }
+}
+
+fn handle_duck_music_events(
+    mut audio_mixer: ResMut<AudioMixer>,
+    mut event_reader: EventReader<DuckMusicEvent>,
+) {
+    for &DuckMusicEvent { level, seconds } in event_reader.read() {
+        // A deeper duck wins over a lighter one that is still going.
+        let level = audio_mixer
+            .ducking
+            .as_ref()
+            .map_or(level, |ducking| ducking.level.min(level));
+
+        audio_mixer.ducking = Some(Ducking {
+            level,
+            hold: Timer::from_seconds(seconds, TimerMode::Once),
+        });
+    }
+}
+
+fn update_mixer(
+    mut audio_mixer: ResMut<AudioMixer>,
+    audio_bus_channels: AudioBusChannels,
+    time: Res<Time<Real>>,
+) {
+    const DUCKING_RELEASE_PER_SECOND: f64 = 2.;
+
+    let audio_mixer = &mut *audio_mixer;
+
+    if let Some(ducking) = &mut audio_mixer.ducking {
+        if ducking.hold.tick(time.delta()).finished() {
+            ducking.level += DUCKING_RELEASE_PER_SECOND * time.delta_seconds_f64();
+        }
+        if ducking.level >= 1. {
+            audio_mixer.ducking = None;
+        }
+    }
+
+    for bus in AudioBus::ALL {
+        let volume = audio_mixer.output_volume(bus);
+
+        if audio_mixer.applied.get(&bus) != Some(&volume) {
+            audio_bus_channels.set_volume(bus, volume);
+            audio_mixer.applied.insert(bus, volume);
+        }
+    }
+
+    for layer in MusicLayer::ALL {
+        let volume = audio_mixer.output_volume(AudioBus::Music) * audio_mixer.layer_gain(layer);
+
+        if audio_mixer.applied_layers.get(&layer) != Some(&volume) {
+            audio_bus_channels.set_layer_volume(layer, volume);
+            audio_mixer.applied_layers.insert(layer, volume);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use bevy::{ecs::schedule::StateTransitionEvent, prelude::*, utils::HashMap};
+use bevy_kira_audio::{AudioEasing, AudioInstance, AudioTween, PlayAudioCommand, PlaybackState};
+
+use crate::{camera::MainCamera, AppState};
+
+use super::{
+    format_music_file_name, spatialize, AudioBus, AudioBusChannels, PlayMusicEvent,
+    PlaybackSettings, StopMusicEvent,
+};
+
+pub(super) struct MusicDirectorPlugin;
+
+impl Plugin for MusicDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicDirector>();
+    }
+}
+
+/// Plays one theme at a time on the music channel and crossfades to the next, leaving the
+/// current theme playing when it is asked for again.
+#[derive(Resource)]
+pub struct MusicDirector {
+    /// Crossfade duration when entering a state from another, for transitions that shouldn't
+    /// use the default one.
+    pub fades: HashMap<(AppState, AppState), Duration>,
+    pub default_fade: Duration,
+    /// The latest state transition, which picks the fade of the themes it starts.
+    transition: Option<(AppState, AppState)>,
+    playing: Option<PlayingTheme>,
+}
+
+struct PlayingTheme {
+    file_name: String,
+    instance_handle: Handle<AudioInstance>,
+    layer_instance_handles: Vec<Handle<AudioInstance>>,
+}
+
+impl Default for MusicDirector {
+    fn default() -> Self {
+        Self {
+            fades: HashMap::from([
+                // Death cuts the battle short.
+                (
+                    (AppState::InGame, AppState::GameOver),
+                    Duration::from_secs_f32(0.5),
+                ),
+                (
+                    (AppState::SpeciesSelect, AppState::InGame),
+                    Duration::from_secs_f32(2.),
+                ),
+            ]),
+            default_fade: Duration::from_secs_f32(1.),
+            transition: None,
+            playing: None,
+        }
+    }
+}
+
+impl MusicDirector {
+    fn fade(&self) -> AudioTween {
+        let duration = self
+            .transition
+            .as_ref()
+            .and_then(|transition| self.fades.get(transition))
+            .copied()
+            .unwrap_or(self.default_fade);
+
+        AudioTween::new(duration, AudioEasing::InOutPowi(2))
+    }
+
+    /// Fades out whatever is playing, layers included.
+    fn stop(&mut self, audio_instances: &mut Assets<AudioInstance>, fade: AudioTween) {
+        let Some(playing_theme) = self.playing.take() else {
+            return;
+        };
+
+        for instance_handle in std::iter::once(&playing_theme.instance_handle)
+            .chain(&playing_theme.layer_instance_handles)
+        {
+            if let Some(audio_instance) = audio_instances.get_mut(instance_handle) {
+                audio_instance.stop(fade.clone());
+            }
+        }
+    }
+}
+
+pub(super) fn handle_play_music_events(
+    mut music_director: ResMut<MusicDirector>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut state_transition_event_reader: EventReader<StateTransitionEvent<AppState>>,
+    mut event_reader: EventReader<PlayMusicEvent>,
+    asset_server: Res<AssetServer>,
+    audio_bus_channels: AudioBusChannels,
+    camera_query: Query<&GlobalTransform, With<MainCamera>>,
+) {
+    let default_settings = PlaybackSettings::default();
+    let listener_position = camera_query
+        .get_single()
+        .ok()
+        .map(|camera_transform| camera_transform.translation().truncate());
+
+    if let Some(event) = state_transition_event_reader.read().last() {
+        music_director.transition = Some((event.before.clone(), event.after.clone()));
+    }
+
+    for event in event_reader.read() {
+        let PlayMusicEvent {
+            file_name,
+            settings,
+            spatial_transform,
+            layers,
+        } = event;
+
+        let still_playing = music_director
+            .playing
+            .as_ref()
+            .filter(|playing_theme| playing_theme.file_name == *file_name)
+            .and_then(|playing_theme| audio_instances.get(&playing_theme.instance_handle))
+            .is_some_and(|audio_instance| {
+                !matches!(
+                    audio_instance.state(),
+                    PlaybackState::Stopped | PlaybackState::Stopping { .. }
+                )
+            });
+
+        if still_playing {
+            continue;
+        }
+
+        let settings = settings.as_ref().unwrap_or(&default_settings);
+        let fade = settings
+            .fade_in
+            .clone()
+            .unwrap_or_else(|| music_director.fade());
+        let (panning, attenuation) = spatial_transform.zip(listener_position).map_or(
+            (settings.panning, 1.),
+            |(spatial_transform, listener_position)| {
+                spatialize(spatial_transform.translation.truncate(), listener_position)
+            },
+        );
+        let apply_settings = |play_audio_command: &mut PlayAudioCommand| {
+            if settings.reverse {
+                play_audio_command.reverse();
+            }
+            if let Some(loop_from) = settings.loop_from {
+                play_audio_command.loop_from(loop_from);
+            }
+            if let Some(loop_until) = settings.loop_until {
+                play_audio_command.loop_until(loop_until);
+            }
+
+            play_audio_command
+                .fade_in(fade.clone())
+                .with_panning(panning)
+                .with_playback_rate(settings.playback_rate)
+                .with_volume(settings.volume * attenuation);
+        };
+
+        music_director.stop(&mut audio_instances, fade.clone());
+
+        let path = format_music_file_name(file_name);
+        let mut play_audio_command = audio_bus_channels.play(
+            AudioBus::Music,
+            asset_server.get_handle(path).unwrap_or_default(),
+        );
+
+        apply_settings(&mut play_audio_command);
+
+        // Layers start along with the theme to stay in sync with it. Themes without stems for
+        // a layer play without it.
+        let layer_instance_handles = layers
+            .iter()
+            .filter_map(|layer| {
+                let path = format_music_file_name(&layer.file_name(file_name));
+                let Some(audio_source) = asset_server
+                    .get_handle(&path)
+                    .filter(|handle| asset_server.is_loaded_with_dependencies(handle))
+                else {
+                    warn!("Playing {file_name} without its {layer:?} layer, {path} isn't loaded.");
+                    return None;
+                };
+                let mut layer_play_audio_command =
+                    audio_bus_channels.play_layer(*layer, audio_source);
+
+                apply_settings(&mut layer_play_audio_command);
+
+                Some(layer_play_audio_command.handle())
+            })
+            .collect();
+
+        music_director.playing = Some(PlayingTheme {
+            file_name: file_name.clone(),
+            instance_handle: play_audio_command.handle(),
+            layer_instance_handles,
+        });
+    }
+}
+
+pub(super) fn handle_stop_music_events(
+    mut music_director: ResMut<MusicDirector>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut event_reader: EventReader<StopMusicEvent>,
+) {
+    if !event_reader.is_empty() {
+        let fade = music_director.fade();
+
+        music_director.stop(&mut audio_instances, fade);
+    }
+    event_reader.clear();
+}
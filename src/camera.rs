@@ -1,7 +1,9 @@
 use bevy::{prelude::*, render::view::RenderLayers};
 
+use rand::Rng;
+
 use crate::{
-    game::Player,
+    game::{Player, PlayerHitEvent},
     game::{GRID_SIZE, HALF_TILE_SIZE, TILE_SIZE},
 };
 
@@ -23,13 +25,18 @@ pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShake>();
+
         app.add_systems(Startup, setup_camera);
 
+        app.add_systems(Update, start_camera_shake_on_player_hit);
+
         app.add_systems(
             PostUpdate,
             (
                 (
                     update_camera.run_if(any_with_component::<Player>),
+                    shake_camera,
                     constrain_camera_position_to_level,
                 )
                     .chain(),
@@ -88,6 +95,23 @@ impl SubLayerCameraBundle {
 #[derive(Component)]
 pub struct MainCamera;
 
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    direction: Vec2,
+    intensity: f32,
+    timer: Timer,
+}
+
+impl CameraShake {
+    pub fn new(direction: Vec2, intensity: f32, seconds: f32) -> Self {
+        Self {
+            direction,
+            intensity,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct YSorted;
 
@@ -118,6 +142,37 @@ fn update_camera(
     camera_transform.translation.y = player_transform.translation.y;
 }
 
+fn start_camera_shake_on_player_hit(
+    mut camera_shake: ResMut<CameraShake>,
+    mut player_hit_event_reader: EventReader<PlayerHitEvent>,
+) {
+    const SHAKE_SECONDS: f32 = 0.25;
+
+    for event in player_hit_event_reader.read() {
+        *camera_shake = CameraShake::new(event.direction, event.damage as f32, SHAKE_SECONDS);
+    }
+}
+
+fn shake_camera(
+    mut camera_query: Query<&mut Transform, (With<Camera2d>, With<MainCamera>)>,
+    mut camera_shake: ResMut<CameraShake>,
+    time: Res<Time>,
+) {
+    if camera_shake.timer.tick(time.delta()).finished() {
+        return;
+    }
+
+    let mut camera_transform = camera_query.single_mut();
+    let mut rng = rand::thread_rng();
+    let strength = camera_shake.intensity * camera_shake.timer.fraction_remaining();
+
+    let jitter = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
+    let offset = (camera_shake.direction + jitter) * strength;
+
+    camera_transform.translation.x += offset.x;
+    camera_transform.translation.y += offset.y;
+}
+
 fn constrain_camera_position_to_level(
     mut camera_query: Query<(&Camera, &mut Transform), (With<Camera2d>, With<MainCamera>)>,
 ) {
@@ -0,0 +1,303 @@
+use bevy::prelude::*;
+
+use crate::{
+    audio::PlayMusicEvent,
+    entity_cleanup,
+    input::{Action, InputBinding, InputBindings},
+    AppState,
+};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingRebind>();
+
+        app.add_systems(
+            OnEnter(AppState::Controls),
+            (setup_controls, play_background_music),
+        );
+
+        // Rebuild the screen whenever a binding changes or starts waiting for a new one.
+        app.add_systems(
+            Update,
+            (
+                handle_controls_button_interactions,
+                capture_rebind,
+                (entity_cleanup::<With<ControlsEntity>>, setup_controls)
+                    .chain()
+                    .run_if(
+                        resource_changed::<InputBindings>
+                            .or_else(resource_changed::<PendingRebind>),
+                    ),
+            )
+                .chain()
+                .run_if(in_state(AppState::Controls)),
+        );
+
+        app.add_systems(
+            OnExit(AppState::Controls),
+            (entity_cleanup::<With<ControlsEntity>>, cancel_rebind),
+        );
+    }
+}
+
+#[derive(Component)]
+struct ControlsEntity;
+
+/// Keyboard and mouse buttons are rebound together, gamepad buttons separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BindingDevice {
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// The action waiting for the player to press its new button, if any.
+#[derive(Resource, Default)]
+struct PendingRebind(Option<(Action, BindingDevice)>);
+
+#[derive(Component)]
+enum ControlsButtonAction {
+    Rebind(Action, BindingDevice),
+    ResetToDefaults,
+    Back,
+}
+
+fn setup_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    input_bindings: Res<InputBindings>,
+    pending_rebind: Res<PendingRebind>,
+) {
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+    let text_style = |font_size: f32| TextStyle {
+        color: Color::BLACK,
+        font: font.clone(),
+        font_size,
+    };
+
+    commands
+        .spawn((
+            ControlsEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::rgb(0.05, 0.05, 0.1)),
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(6.),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|node| {
+            node.spawn(TextBundle::from_section(
+                "Controls",
+                TextStyle {
+                    color: Color::WHITE,
+                    ..text_style(64.)
+                },
+            ));
+
+            node.spawn(TextBundle::from_section(
+                "Click a binding, then press the new key or button. Click it again to cancel.",
+                TextStyle {
+                    color: Color::GRAY,
+                    ..text_style(20.)
+                },
+            ));
+
+            for action in Action::ALL {
+                node.spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(TextBundle {
+                        style: Style {
+                            width: Val::Px(240.),
+                            ..default()
+                        },
+                        text: Text::from_section(
+                            action.name(),
+                            TextStyle {
+                                color: Color::WHITE,
+                                ..text_style(24.)
+                            },
+                        ),
+                        ..default()
+                    });
+
+                    for device in [BindingDevice::KeyboardMouse, BindingDevice::Gamepad] {
+                        let label = if pending_rebind.0 == Some((action, device)) {
+                            "Press a button...".to_string()
+                        } else {
+                            let names = input_bindings
+                                .get(&action)
+                                .into_iter()
+                                .flatten()
+                                .filter(|binding| binding_device(binding) == device)
+                                .map(InputBinding::name)
+                                .collect::<Vec<_>>();
+
+                            if names.is_empty() {
+                                "-".to_string()
+                            } else {
+                                names.join(" / ")
+                            }
+                        };
+
+                        row.spawn((
+                            ButtonBundle {
+                                background_color: Color::ALICE_BLUE.into(),
+                                style: Style {
+                                    justify_content: JustifyContent::Center,
+                                    width: Val::Px(240.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            ControlsButtonAction::Rebind(action, device),
+                        ))
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(label, text_style(24.)));
+                        });
+                    }
+                });
+            }
+
+            for (label, action) in [
+                ("Reset to Defaults", ControlsButtonAction::ResetToDefaults),
+                ("Back to Menu", ControlsButtonAction::Back),
+            ] {
+                node.spawn((
+                    ButtonBundle {
+                        background_color: Color::ALICE_BLUE.into(),
+                        style: Style {
+                            padding: UiRect::horizontal(Val::Px(8.)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    action,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(label, text_style(32.)));
+                });
+            }
+        });
+}
+
+fn binding_device(binding: &InputBinding) -> BindingDevice {
+    if binding.is_gamepad() {
+        BindingDevice::Gamepad
+    } else {
+        BindingDevice::KeyboardMouse
+    }
+}
+
+fn handle_controls_button_interactions(
+    mut app_state: ResMut<NextState<AppState>>,
+    mut input_bindings: ResMut<InputBindings>,
+    mut pending_rebind: ResMut<PendingRebind>,
+    query: Query<(&Interaction, &ControlsButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    // Clicks are fair game as new bindings while a rebind is pending.
+    if pending_rebind.0.is_some() {
+        return;
+    }
+
+    for (interaction, controls_button_action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *controls_button_action {
+            ControlsButtonAction::Rebind(action, device) => {
+                pending_rebind.0 = Some((action, device));
+            }
+            ControlsButtonAction::ResetToDefaults => {
+                *input_bindings = InputBindings::default();
+                input_bindings.save();
+            }
+            ControlsButtonAction::Back => app_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+/// Replaces the pending action's bindings for its device with the first button pressed. An
+/// action already using that button gets the pending action's old binding in exchange, and
+/// clicking the pending binding again cancels the rebind.
+fn capture_rebind(
+    mut input_bindings: ResMut<InputBindings>,
+    mut pending_rebind: ResMut<PendingRebind>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    query: Query<(&Interaction, &ControlsButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    // The click that started the rebind must not end it.
+    if pending_rebind.is_changed() {
+        return;
+    }
+
+    let Some((action, device)) = pending_rebind.0 else {
+        return;
+    };
+
+    let cancelled = query.iter().any(|(interaction, controls_button_action)| {
+        *interaction == Interaction::Pressed
+            && matches!(
+                *controls_button_action,
+                ControlsButtonAction::Rebind(pending_action, pending_device)
+                    if (pending_action, pending_device) == (action, device)
+            )
+    });
+
+    if cancelled {
+        pending_rebind.0 = None;
+        return;
+    }
+
+    let new_binding = match device {
+        BindingDevice::KeyboardMouse => keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|&key| InputBinding::Key(key))
+            .or_else(|| {
+                mouse_input
+                    .get_just_pressed()
+                    .next()
+                    .map(|&button| InputBinding::Mouse(button))
+            }),
+        BindingDevice::Gamepad => gamepad_input
+            .get_just_pressed()
+            .next()
+            .map(|button| InputBinding::Gamepad(button.button_type)),
+    };
+
+    let Some(new_binding) = new_binding else {
+        return;
+    };
+
+    input_bindings.rebind(action, new_binding);
+    input_bindings.save();
+    pending_rebind.0 = None;
+}
+
+fn cancel_rebind(mut pending_rebind: ResMut<PendingRebind>) {
+    pending_rebind.0 = None;
+}
+
+fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
+    play_music_event_writer.send(PlayMusicEvent::new_with_defaults("theme1.ogg"));
+}
@@ -0,0 +1,274 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_rapier2d::prelude::Velocity;
+use serde::{Deserialize, Serialize};
+
+use crate::{audio::DuckMusicEvent, playing};
+
+use super::{
+    combat::{Invulnerable, Knockback, Projectile, Stunned},
+    enemy::EnemyKind,
+    Enemy, Player,
+};
+
+pub(super) struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UseAbilityEvent>();
+
+        app.init_resource::<AbilityRegistry>();
+
+        app.add_systems(
+            FixedUpdate,
+            (update_ability_cooldowns, use_abilities, update_dashing)
+                .chain()
+                .run_if(playing()),
+        );
+    }
+}
+
+#[derive(Event)]
+pub struct UseAbilityEvent {
+    kind: AbilityKind,
+}
+
+impl UseAbilityEvent {
+    pub fn new(kind: AbilityKind) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AbilityKind {
+    Dash,
+    TailSwipe,
+    Roar,
+}
+
+impl AbilityKind {
+    pub const ALL: [Self; 3] = [Self::Dash, Self::TailSwipe, Self::Roar];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Dash => "Dash",
+            Self::TailSwipe => "Tail",
+            Self::Roar => "Roar",
+        }
+    }
+}
+
+/// Describes an ability. Not every field applies to every ability.
+pub struct AbilityDefinition {
+    pub cooldown: f32,
+    /// Seconds the ability's effect lasts.
+    pub duration: f32,
+    pub radius: f32,
+    pub strength: f32,
+}
+
+#[derive(Resource, Deref)]
+pub struct AbilityRegistry(HashMap<AbilityKind, AbilityDefinition>);
+
+impl Default for AbilityRegistry {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (
+                AbilityKind::Dash,
+                AbilityDefinition {
+                    cooldown: 3.,
+                    duration: 0.25,
+                    radius: 0.,
+                    strength: 1600.,
+                },
+            ),
+            (
+                AbilityKind::TailSwipe,
+                AbilityDefinition {
+                    cooldown: 5.,
+                    duration: 0.,
+                    radius: 120.,
+                    // Units per second the enemies are flung back at.
+                    strength: 320.,
+                },
+            ),
+            (
+                AbilityKind::Roar,
+                AbilityDefinition {
+                    cooldown: 12.,
+                    duration: 2.5,
+                    radius: 250.,
+                    strength: 0.,
+                },
+            ),
+        ]))
+    }
+}
+
+/// The abilities the player has unlocked, each with its own cooldown.
+#[derive(Component)]
+pub struct Abilities(HashMap<AbilityKind, Timer>);
+
+impl Abilities {
+    pub fn new(kinds: impl IntoIterator<Item = AbilityKind>) -> Self {
+        Self(
+            kinds
+                .into_iter()
+                .map(|kind| (kind, Timer::default()))
+                .collect(),
+        )
+    }
+
+    pub fn is_unlocked(&self, kind: AbilityKind) -> bool {
+        self.0.contains_key(&kind)
+    }
+
+    pub fn is_ready(&self, kind: AbilityKind) -> bool {
+        self.0.get(&kind).is_some_and(Timer::finished)
+    }
+
+    /// Fraction of the cooldown left, 0 when the ability is ready.
+    pub fn cooldown_remaining(&self, kind: AbilityKind) -> f32 {
+        self.0
+            .get(&kind)
+            .filter(|cooldown| !cooldown.finished())
+            .map_or(0., Timer::fraction_remaining)
+    }
+}
+
+impl Default for Abilities {
+    fn default() -> Self {
+        Self::new(AbilityKind::ALL)
+    }
+}
+
+/// The dragon keeps its dash velocity and ignores steering until the timer finishes.
+#[derive(Component, Deref, DerefMut)]
+pub struct Dashing(Timer);
+
+fn update_ability_cooldowns(mut query: Query<&mut Abilities>, time: Res<Time>) {
+    for mut abilities in &mut query {
+        for cooldown in abilities.0.values_mut() {
+            cooldown.tick(time.delta());
+        }
+    }
+}
+
+fn use_abilities(
+    mut commands: Commands,
+    mut use_ability_event_reader: EventReader<UseAbilityEvent>,
+    mut duck_music_event_writer: EventWriter<DuckMusicEvent>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut Abilities,
+            Option<&mut Invulnerable>,
+        ),
+        With<Player>,
+    >,
+    enemy_query: Query<(Entity, &Transform, &EnemyKind), With<Enemy>>,
+    projectile_query: Query<(Entity, &Transform), With<Projectile>>,
+    ability_registry: Res<AbilityRegistry>,
+) {
+    let Ok((
+        player_entity,
+        player_transform,
+        mut player_velocity,
+        mut abilities,
+        mut player_invulnerable,
+    )) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+
+    for &UseAbilityEvent { kind } in use_ability_event_reader.read() {
+        let Some(definition) = ability_registry.get(&kind) else {
+            continue;
+        };
+
+        if !abilities.is_ready(kind) {
+            continue;
+        }
+
+        abilities.0.insert(
+            kind,
+            Timer::from_seconds(definition.cooldown, TimerMode::Once),
+        );
+
+        match kind {
+            AbilityKind::Dash => {
+                let facing = player_transform.rotation.mul_vec3(Vec3::Y).truncate();
+
+                player_velocity.linvel = facing * definition.strength;
+                commands
+                    .entity(player_entity)
+                    .insert(Dashing(Timer::from_seconds(
+                        definition.duration,
+                        TimerMode::Once,
+                    )));
+
+                // Dashing right after a hit mustn't cut the hit's invulnerability short.
+                let invulnerable = Invulnerable::evasive(definition.duration);
+                match player_invulnerable.as_deref_mut() {
+                    Some(current) => current.extend(invulnerable),
+                    None => {
+                        commands.entity(player_entity).insert(invulnerable);
+                    }
+                }
+            }
+            AbilityKind::TailSwipe => {
+                for (enemy_entity, enemy_transform, &kind) in &enemy_query {
+                    let offset = enemy_transform.translation.truncate() - player_position;
+
+                    // Buildings don't budge.
+                    if kind != EnemyKind::Building && offset.length() <= definition.radius {
+                        commands
+                            .entity(enemy_entity)
+                            .insert(Knockback(offset.normalize_or_zero() * definition.strength));
+                    }
+                }
+
+                for (projectile_entity, projectile_transform) in &projectile_query {
+                    if projectile_transform
+                        .translation
+                        .truncate()
+                        .distance(player_position)
+                        <= definition.radius
+                    {
+                        commands.entity(projectile_entity).despawn_recursive();
+                    }
+                }
+            }
+            AbilityKind::Roar => {
+                duck_music_event_writer.send(DuckMusicEvent::new(0.3, definition.duration));
+
+                for (enemy_entity, enemy_transform, _) in &enemy_query {
+                    if enemy_transform
+                        .translation
+                        .truncate()
+                        .distance(player_position)
+                        <= definition.radius
+                    {
+                        commands
+                            .entity(enemy_entity)
+                            .insert(Stunned::new(definition.duration));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn update_dashing(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Dashing)>,
+    time: Res<Time>,
+) {
+    for (entity, mut dashing) in &mut query {
+        if dashing.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Dashing>();
+        }
+    }
+}
@@ -1,32 +1,55 @@
 use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_rapier2d::prelude::*;
+use std::{collections::HashSet, f32::consts::PI};
 
 use crate::{
+    audio::PlaySoundEffectEvent,
     camera::{RenderLayer, YSorted},
+    lair::DragonSkin,
     playing,
 };
 
 use super::{
+    enemy::EnemyKind,
     power_up::{PowerUpEvent, PowerUpEventType},
+    projectile::{
+        ProjectileAtlas, ProjectileEffect, ProjectileKind, ProjectileMaterial, ProjectileRegistry,
+    },
     resource_pool::{Fire, Health, ResourcePool},
     score_system::{ScoreEvent, ScoreEventType},
-    Enemy, InGameEntity, Player, PLAYER_GROUP, PROJECTILE_GROUP, TILE_SIZE,
+    Altitude, BreathElement, Enemy, InGameEntity, Player, ANTI_AIR_GROUP, FIRE_BREATH_GROUP,
+    PLAYER_GROUP, PROJECTILE_GROUP,
 };
 
 pub(super) struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>();
+        app.add_event::<EnemyKilledEvent>();
+        app.add_event::<PlayerHitEvent>();
         app.add_event::<SpawnProjectileEvent>();
 
+        app.init_resource::<ProjectileAtlas>();
+        app.init_resource::<ProjectileRegistry>();
+
         app.add_systems(
             FixedUpdate,
             (
-                projectile_collision_with_player,
+                (
+                    update_projectile_lifetimes,
+                    projectile_collision_with_player,
+                    despawn_projectiles,
+                )
+                    .chain(),
+                update_projectile_arcs,
+                update_invulnerability,
+                update_status_effects,
+                apply_knockback,
                 spawn_projectiles,
-                despawn_projectiles,
                 despawn_dead_entities,
                 compute_damage_from_intersections,
+                claw_buildings,
             )
                 .run_if(playing()),
         );
@@ -38,33 +61,95 @@ pub struct SpawnProjectileEvent {
     damage: i16,
     direction: Vec2,
     emitter: Entity,
+    kind: ProjectileKind,
     position: Vec2,
     speed: f32,
 }
 
 impl SpawnProjectileEvent {
-    pub fn new(damage: i16, direction: Vec2, emitter: Entity, position: Vec2, speed: f32) -> Self {
+    pub fn new(
+        damage: i16,
+        direction: Vec2,
+        emitter: Entity,
+        kind: ProjectileKind,
+        position: Vec2,
+        speed: f32,
+    ) -> Self {
         Self {
             damage,
             direction,
             emitter,
+            kind,
             position,
             speed,
         }
     }
 }
 
+/// Sent whenever the player damages another Entity.
+#[derive(Event)]
+pub struct DamageEvent {
+    pub amount: i16,
+    pub position: Vec2,
+    pub target: Entity,
+}
+
+impl DamageEvent {
+    pub fn new(amount: i16, position: Vec2, target: Entity) -> Self {
+        Self {
+            amount,
+            position,
+            target,
+        }
+    }
+}
+
+/// Sent whenever an enemy or building is destroyed.
+#[derive(Event)]
+pub struct EnemyKilledEvent {
+    pub kind: EnemyKind,
+}
+
+impl EnemyKilledEvent {
+    pub fn new(kind: EnemyKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// Sent whenever a projectile damages the player.
+#[derive(Event)]
+pub struct PlayerHitEvent {
+    pub damage: i16,
+    pub direction: Vec2,
+    /// Where the projectile struck.
+    pub position: Vec2,
+}
+
+impl PlayerHitEvent {
+    pub fn new(damage: i16, direction: Vec2, position: Vec2) -> Self {
+        Self {
+            damage,
+            direction,
+            position,
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct ProjectileBundle {
     pub collider: Collider,
     pub collision_groups: CollisionGroups,
     pub ccd: Ccd,
     pub damage: ImpactDamage,
+    pub effect: ProjectileEffect,
     pub emitter: Emitter,
+    pub kind: ProjectileKind,
+    pub lifetime: Lifetime,
     pub marker: Projectile,
+    pub material: ProjectileMaterial,
     pub render_layers: RenderLayers,
     pub rigid_body: RigidBody,
-    pub sprite: SpriteBundle,
+    pub sprite: SpriteSheetBundle,
     pub velocity: Velocity,
 }
 
@@ -94,19 +179,114 @@ impl AttackTimer {
 #[derive(Component)]
 pub struct Projectile;
 
+/// Prevents an Entity from taking damage until the timer finishes. Only invulnerability from
+/// being hit makes the sprite flash.
+#[derive(Component, Deref, DerefMut)]
+pub struct Invulnerable {
+    #[deref]
+    timer: Timer,
+    flashing: bool,
+}
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            flashing: true,
+        }
+    }
+
+    /// Invulnerability the Entity grants itself, e.g. while dashing.
+    pub fn evasive(seconds: f32) -> Self {
+        Self {
+            flashing: false,
+            ..Self::new(seconds)
+        }
+    }
+
+    /// Replaces the invulnerability with `other` if it lasts longer.
+    pub fn extend(&mut self, other: Self) {
+        if other.remaining_secs() > self.remaining_secs() {
+            *self = other;
+        }
+    }
+}
+
+/// Pushes an Entity away. Entities with a [`Velocity`] get it added as an impulse on the next
+/// tick, the rest are displaced by it every tick while it decays.
+#[derive(Component)]
+pub struct Knockback(pub Vec2);
+
+/// Despawns the Entity once the timer finishes.
+#[derive(Component, Deref, DerefMut)]
+pub struct Lifetime(Timer);
+
+impl Lifetime {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// Multiplies an Entity's speed by `factor` until the timer finishes.
+#[derive(Component)]
+pub struct Slowed {
+    pub factor: f32,
+    pub timer: Timer,
+}
+
+impl Slowed {
+    pub fn new(factor: f32, seconds: f32) -> Self {
+        Self {
+            factor,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+/// Prevents an Entity from moving until the timer finishes. A netted dragon is dragged down to
+/// low altitude and can't climb until it is free.
+#[derive(Component, Deref, DerefMut)]
+pub struct Netted(Timer);
+
+impl Netted {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// Prevents an Entity from moving and attacking until the timer finishes.
+#[derive(Component, Deref, DerefMut)]
+pub struct Stunned(Timer);
+
+impl Stunned {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
 fn spawn_projectiles(
     mut commands: Commands,
     mut spawn_projectile_event_reader: EventReader<SpawnProjectileEvent>,
+    mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+    asset_server: Res<AssetServer>,
+    projectile_atlas: Res<ProjectileAtlas>,
+    projectile_registry: Res<ProjectileRegistry>,
 ) {
+    // A volley is heard once per emitter rather than once per projectile.
+    let mut sounding_emitters = HashSet::new();
+
     for &SpawnProjectileEvent {
         damage,
         direction,
         emitter,
+        kind,
         position,
         speed,
     } in spawn_projectile_event_reader.read()
     {
-        let size = Vec2::new(TILE_SIZE.x, 4.);
+        let Some(definition) = projectile_registry.get(&kind) else {
+            continue;
+        };
         let angle = if direction != Vec2::ZERO {
             let mut angle = (direction).angle_between(Vec2::X);
             if !angle.is_finite() {
@@ -117,37 +297,59 @@ fn spawn_projectiles(
             0.
         };
 
+        if sounding_emitters.insert((emitter, definition.launch_sound)) {
+            play_sound_effect_event_writer.send(PlaySoundEffectEvent::new(
+                definition.launch_sound,
+                None,
+                Some(Transform::from_translation(position.extend(0.))),
+            ));
+        }
+
         let mut projectile_entity_commands = commands.spawn(ProjectileBundle {
             ccd: Ccd::enabled(),
-            collider: Collider::cuboid(size.x / 2., size.y / 2.),
+            collider: definition.collider.clone(),
             collision_groups: CollisionGroups::new(
-                PROJECTILE_GROUP,
-                PLAYER_GROUP | PROJECTILE_GROUP,
+                if definition.anti_air {
+                    PROJECTILE_GROUP | ANTI_AIR_GROUP
+                } else {
+                    PROJECTILE_GROUP
+                },
+                PLAYER_GROUP | PROJECTILE_GROUP | FIRE_BREATH_GROUP,
             ),
             damage: ImpactDamage(damage),
+            effect: definition.effect,
             emitter: Emitter(emitter),
+            kind,
+            lifetime: Lifetime::new(definition.lifetime),
             marker: Projectile,
+            material: definition.material,
             render_layers: RenderLayers::layer(RenderLayer::Sky.into()),
             rigid_body: RigidBody::Dynamic,
-            sprite: SpriteBundle {
+            sprite: SpriteSheetBundle {
+                atlas: TextureAtlas {
+                    layout: projectile_atlas.clone(),
+                    index: definition.atlas_index,
+                },
                 sprite: Sprite {
-                    color: Color::BLACK,
-                    custom_size: Some(size),
+                    custom_size: Some(definition.size),
                     ..default()
                 },
+                texture: asset_server
+                    .get_handle("textures/projectiles.png")
+                    .unwrap_or_default(),
                 transform: Transform::from_translation(position.extend(1.0))
                     .with_rotation(Quat::from_rotation_z(-angle)),
                 ..default()
             },
             velocity: Velocity {
-                linvel: direction * speed,
+                linvel: direction * speed * definition.speed_factor,
                 angvel: 0.,
             },
         });
 
         projectile_entity_commands.insert((
             Damping {
-                linear_damping: 1.0,
+                linear_damping: definition.damping,
                 angular_damping: 10.0,
             },
             InGameEntity,
@@ -156,59 +358,332 @@ fn spawn_projectiles(
     }
 }
 
+fn update_projectile_lifetimes(mut query: Query<&mut Lifetime>, time: Res<Time>) {
+    for mut lifetime in &mut query {
+        lifetime.tick(time.delta());
+    }
+}
+
+/// Scales projectiles up towards the middle of their flight to fake a parabolic arc.
+fn update_projectile_arcs(
+    mut query: Query<(&ProjectileKind, &Lifetime, &mut Sprite), With<Projectile>>,
+    projectile_registry: Res<ProjectileRegistry>,
+) {
+    for (kind, lifetime, mut sprite) in &mut query {
+        if let Some(definition) = projectile_registry.get(kind) {
+            let height = (lifetime.fraction() * PI).sin() * definition.arc_height;
+            sprite.custom_size = Some(definition.size * (1. + height));
+        }
+    }
+}
+
 fn projectile_collision_with_player(
     mut commands: Commands,
     mut score_event_writer: EventWriter<ScoreEvent>,
-    mut player_query: Query<(Entity, &mut ResourcePool<Health>), With<Player>>,
-    projectile_query: Query<(Entity, &ImpactDamage), With<Projectile>>,
+    mut player_hit_event_writer: EventWriter<PlayerHitEvent>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut ResourcePool<Health>,
+            &mut Altitude,
+            Has<Invulnerable>,
+        ),
+        With<Player>,
+    >,
+    projectile_query: Query<
+        (
+            Entity,
+            &Transform,
+            &ImpactDamage,
+            &ProjectileEffect,
+            &Lifetime,
+            &Velocity,
+        ),
+        (With<Projectile>, Without<Player>),
+    >,
     rapier_context: Res<RapierContext>,
 ) {
-    let (player_entity, mut player_hitpoints) = player_query.single_mut(); // A first entity with a collider attached.
+    const INVULNERABILITY_SECONDS: f32 = 1.0;
+    const KNOCKBACK_STRENGTH: f32 = 150.;
+
+    let (
+        player_entity,
+        player_transform,
+        mut player_hitpoints,
+        mut player_altitude,
+        mut invulnerable,
+    ) = player_query.single_mut(); // A first entity with a collider attached.
+    let player_position = player_transform.translation.truncate();
+
+    for (
+        projectile_entity,
+        projectile_transform,
+        projectile_damage,
+        projectile_effect,
+        projectile_lifetime,
+        projectile_velocity,
+    ) in &projectile_query
+    {
+        let projectile_position = projectile_transform.translation.truncate();
+        let direct_hit = rapier_context
+            .contact_pair(player_entity, projectile_entity)
+            .is_some_and(|contact_pair| contact_pair.has_any_active_contacts());
+        // Splash damage spreads on the ground, so it can't reach the dragon while it flies high.
+        let splash_hit = match *projectile_effect {
+            ProjectileEffect::Splash { radius } if *player_altitude == Altitude::Low => {
+                projectile_lifetime.just_finished()
+                    && projectile_position.distance(player_position) <= radius
+            }
+            _ => false,
+        };
+
+        if direct_hit || splash_hit {
+            // Only the first projectile of a volley lands, the rest are absorbed.
+            if !invulnerable {
+                let direction = if direct_hit {
+                    projectile_velocity.linvel.normalize_or_zero()
+                } else {
+                    (player_position - projectile_position).normalize_or_zero()
+                };
+                let mut player_entity_commands = commands.entity(player_entity);
 
-    for (projectile_entity, projectile_damage) in &projectile_query {
-        if let Some(contact_pair) = rapier_context.contact_pair(player_entity, projectile_entity) {
-            if contact_pair.has_any_active_contacts() {
                 player_hitpoints.subtract(projectile_damage.0);
-                score_event_writer.send(ScoreEvent::new(0, ScoreEventType::ResetMultiplier));
+                score_event_writer.send(ScoreEvent::new(
+                    0,
+                    ScoreEventType::ResetMultiplier,
+                    Some(player_position),
+                ));
+                player_hit_event_writer.send(PlayerHitEvent::new(
+                    projectile_damage.0,
+                    direction,
+                    projectile_position,
+                ));
+                player_entity_commands.insert((
+                    Invulnerable::new(INVULNERABILITY_SECONDS),
+                    Knockback(direction * KNOCKBACK_STRENGTH),
+                ));
 
-                // TODO: Add "death" component or event and use it here so a different system handles despawns.
-                commands.entity(projectile_entity).despawn_recursive();
+                match *projectile_effect {
+                    ProjectileEffect::Slow { factor, seconds } => {
+                        player_entity_commands.insert(Slowed::new(factor, seconds));
+                    }
+                    ProjectileEffect::Net { seconds } => {
+                        player_entity_commands.insert(Netted::new(seconds));
+                        *player_altitude = Altitude::Low;
+                    }
+                    ProjectileEffect::Damage | ProjectileEffect::Splash { .. } => {}
+                }
+
+                invulnerable = true;
             }
+
+            // TODO: Add "death" component or event and use it here so a different system handles despawns.
+            commands.entity(projectile_entity).despawn_recursive();
+        }
+    }
+}
+
+fn update_invulnerability(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Sprite, Option<&DragonSkin>)>,
+    time: Res<Time>,
+) {
+    const FLASH_INTERVAL_SECONDS: f32 = 0.1;
+
+    for (entity, mut invulnerable, mut sprite, skin) in &mut query {
+        let color = skin.map_or(Color::WHITE, DragonSkin::color);
+
+        if invulnerable.tick(time.delta()).finished() {
+            sprite.color = color;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else if !invulnerable.flashing {
+            sprite.color = color;
+        } else {
+            let flash_on = (invulnerable.elapsed_secs() / FLASH_INTERVAL_SECONDS) as u32 % 2 == 0;
+            sprite.color = if flash_on {
+                Color::rgba(1., 0.3, 0.3, 0.6)
+            } else {
+                color
+            };
+        }
+    }
+}
+
+fn update_status_effects(
+    mut commands: Commands,
+    mut slowed_query: Query<(Entity, &mut Slowed)>,
+    mut netted_query: Query<(Entity, &mut Netted)>,
+    mut stunned_query: Query<(Entity, &mut Stunned)>,
+    time: Res<Time>,
+) {
+    for (entity, mut slowed) in &mut slowed_query {
+        if slowed.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Slowed>();
+        }
+    }
+
+    for (entity, mut netted) in &mut netted_query {
+        if netted.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Netted>();
+        }
+    }
+
+    for (entity, mut stunned) in &mut stunned_query {
+        if stunned.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    mut velocity_query: Query<(Entity, &mut Velocity, &Knockback)>,
+    mut displaced_query: Query<(Entity, &mut Transform, &mut Knockback), Without<Velocity>>,
+    time: Res<Time>,
+) {
+    // Fraction of a displacing knockback lost every second.
+    const DECAY: f32 = 8.;
+    // Units per second below which a displacing knockback is over.
+    const MIN_SPEED: f32 = 10.;
+
+    for (entity, mut velocity, knockback) in &mut velocity_query {
+        velocity.linvel += knockback.0;
+        commands.entity(entity).remove::<Knockback>();
+    }
+
+    for (entity, mut transform, mut knockback) in &mut displaced_query {
+        transform.translation += (knockback.0 * time.delta_seconds()).extend(0.);
+        knockback.0 *= (1. - DECAY * time.delta_seconds()).max(0.);
+
+        if knockback.0.length() < MIN_SPEED {
+            commands.entity(entity).remove::<Knockback>();
         }
     }
 }
 
 fn compute_damage_from_intersections(
-    mut enemy_query: Query<&mut ResourcePool<Health>, With<Enemy>>,
+    mut commands: Commands,
+    mut damage_event_writer: EventWriter<DamageEvent>,
+    mut enemy_query: Query<(&mut ResourcePool<Health>, &Transform), With<Enemy>>,
     fire_query: Query<(Entity, &ImpactDamage), With<Fire>>,
+    player_query: Query<&BreathElement, With<Player>>,
     rapier_context: Res<RapierContext>,
 ) {
+    const ICE_SLOW_FACTOR: f32 = 0.4;
+    const ICE_SLOW_SECONDS: f32 = 2.;
+    // Ice slows rather than burns, so only the strongest blasts chill enemies to death.
+    const ICE_DAMAGE_DIVISOR: i16 = 4;
+
+    let breath_element = player_query.get_single().copied().unwrap_or_default();
+
     for (entity, damage) in &fire_query {
         for (entity1, entity2, intersecting) in rapier_context.intersection_pairs_with(entity) {
             let other_entity = if entity1 == entity { entity2 } else { entity1 };
 
             if intersecting {
-                if let Ok(mut enemy_hitpoints) = enemy_query.get_mut(other_entity) {
-                    enemy_hitpoints.subtract(damage.0);
+                if let Ok((mut enemy_hitpoints, enemy_transform)) =
+                    enemy_query.get_mut(other_entity)
+                {
+                    let amount = if breath_element == BreathElement::Ice {
+                        commands
+                            .entity(other_entity)
+                            .try_insert(Slowed::new(ICE_SLOW_FACTOR, ICE_SLOW_SECONDS));
+
+                        damage.0 / ICE_DAMAGE_DIVISOR
+                    } else {
+                        damage.0
+                    }
+                    .min(enemy_hitpoints.current());
+
+                    if amount > 0 {
+                        enemy_hitpoints.subtract(amount);
+                        damage_event_writer.send(DamageEvent::new(
+                            amount,
+                            enemy_transform.translation.truncate(),
+                            other_entity,
+                        ));
+                    }
                 }
             }
         }
     }
 }
 
+/// While flying low the dragon rakes any building it passes over with its claws.
+fn claw_buildings(
+    mut damage_event_writer: EventWriter<DamageEvent>,
+    mut building_query: Query<(&mut ResourcePool<Health>, &Transform, &EnemyKind), With<Enemy>>,
+    mut claw_cooldown: Local<f32>,
+    player_query: Query<(&Transform, &Altitude), With<Player>>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    const CLAW_DAMAGE: i16 = 25;
+    const CLAW_INTERVAL_SECONDS: f32 = 0.5;
+    const CLAW_RANGE: f32 = 30.;
+
+    *claw_cooldown = (*claw_cooldown - time.delta_seconds()).max(0.);
+
+    let Ok((player_transform, player_altitude)) = player_query.get_single() else {
+        return;
+    };
+
+    if *player_altitude != Altitude::Low || *claw_cooldown > 0. {
+        return;
+    }
+
+    let mut clawed = false;
+
+    rapier_context.intersections_with_shape(
+        player_transform.translation.truncate(),
+        0.,
+        &Collider::ball(CLAW_RANGE),
+        QueryFilter::new(),
+        |entity| {
+            if let Ok((mut building_hitpoints, building_transform, &kind)) =
+                building_query.get_mut(entity)
+            {
+                let amount = CLAW_DAMAGE.min(building_hitpoints.current());
+
+                if kind == EnemyKind::Building && amount > 0 {
+                    building_hitpoints.subtract(amount);
+                    damage_event_writer.send(DamageEvent::new(
+                        amount,
+                        building_transform.translation.truncate(),
+                        entity,
+                    ));
+                    clawed = true;
+                }
+            }
+            true
+        },
+    );
+
+    if clawed {
+        *claw_cooldown = CLAW_INTERVAL_SECONDS;
+    }
+}
+
 fn despawn_dead_entities(
     mut commands: Commands,
+    mut enemy_killed_event_writer: EventWriter<EnemyKilledEvent>,
     mut score_event_writer: EventWriter<ScoreEvent>,
     mut powerup_event_writer: EventWriter<PowerUpEvent>,
     query: Query<
-        (Entity, &ResourcePool<Health>, &Transform),
+        (Entity, &ResourcePool<Health>, &Transform, &EnemyKind),
         (Without<Player>, Changed<ResourcePool<Health>>),
     >,
 ) {
-    for (entity, health, transform) in &query {
+    for (entity, health, transform, &kind) in &query {
         if health.current() == 0 {
             commands.entity(entity).despawn_recursive();
-            score_event_writer.send(ScoreEvent::new(10, ScoreEventType::AddPoints));
+            enemy_killed_event_writer.send(EnemyKilledEvent::new(kind));
+            score_event_writer.send(ScoreEvent::new(
+                10,
+                ScoreEventType::AddPoints,
+                Some(transform.translation.truncate()),
+            ));
             powerup_event_writer.send(PowerUpEvent::new(
                 *transform,
                 PowerUpEventType::HealingScale,
@@ -219,10 +694,10 @@ fn despawn_dead_entities(
 
 fn despawn_projectiles(
     mut commands: Commands,
-    projectile_query: Query<(Entity, &Velocity), With<Projectile>>,
+    projectile_query: Query<(Entity, &Velocity, &Lifetime), With<Projectile>>,
 ) {
-    for (entity, velocity) in &projectile_query {
-        if velocity.linvel.length() < 60. {
+    for (entity, velocity, lifetime) in &projectile_query {
+        if lifetime.finished() || velocity.linvel.length() < 60. {
             // TODO: Decouple this with a Despawn component
             commands.entity(entity).despawn_recursive();
         }
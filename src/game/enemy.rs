@@ -1,4 +1,4 @@
-use bevy::{prelude::*, render::view::RenderLayers};
+use bevy::{ecs::system::SystemParam, prelude::*, render::view::RenderLayers};
 use bevy_rapier2d::prelude::*;
 use rand::{seq::IteratorRandom, Rng};
 use std::{collections::HashMap, time::Duration};
@@ -7,11 +7,14 @@ use crate::{
     animation::{AnimationIndices, AnimationTimer},
     camera::{RenderLayer, YSorted},
     physics::Speed,
-    playing, AppState,
+    playing,
+    settings::Settings,
+    AppState,
 };
 
 use super::{
-    combat::{AttackDamage, AttackTimer, Range, SpawnProjectileEvent},
+    combat::{AttackDamage, AttackTimer, Range, Slowed, SpawnProjectileEvent, Stunned},
+    projectile::ProjectileKind,
     resource_pool::{Health, ResourcePool},
     BorderTile, InGameEntity, Player, BUILDING_GROUP, ENEMY_GROUP, FIRE_BREATH_GROUP,
     HALF_TILE_SIZE, TILE_SIZE,
@@ -69,7 +72,9 @@ pub struct EnemyBundle {
     pub attack_timer: AttackTimer,
     pub behavior: Behavior,
     pub hitpoints: ResourcePool<Health>,
+    pub kind: EnemyKind,
     pub marker: Enemy,
+    pub projectile_kind: ProjectileKind,
     pub range: Range,
     pub speed: Speed,
     pub animation_indices: AnimationIndices,
@@ -105,8 +110,22 @@ pub enum SpriteAnimation {
 #[derive(Component)]
 pub struct Enemy;
 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnemyKind {
+    Archer,
+    Axeman,
+    Building,
+}
+
 #[derive(Resource)]
-struct EnemySpawnCounter(u32);
+pub(super) struct EnemySpawnCounter(u32);
+
+impl EnemySpawnCounter {
+    /// Spawns come faster every ten enemies, which makes a wave.
+    pub fn wave(&self) -> u32 {
+        self.0 / 10
+    }
+}
 
 #[derive(Resource, Deref, DerefMut)]
 struct EnemySpawnTimer(Timer);
@@ -141,19 +160,50 @@ fn load_atlas_handlers(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(TextureAxeAtlasHandle(texture_atlas_handle_axe));
 }
 
+/// The sprite sheets of every kind of enemy that can be spawned.
+#[derive(SystemParam)]
+struct EnemyTextures<'w> {
+    asset_server: Res<'w, AssetServer>,
+    texture_archer_atlas_handle: Res<'w, TextureArcherAtlasHandle>,
+    texture_axeman_atlas_handle: Res<'w, TextureAxeAtlasHandle>,
+}
+
+impl EnemyTextures<'_> {
+    fn archer(&self) -> (Handle<TextureAtlasLayout>, Handle<Image>) {
+        (
+            self.texture_archer_atlas_handle.0.clone(),
+            self.texture("textures/enemy_archer.png"),
+        )
+    }
+
+    fn axeman(&self) -> (Handle<TextureAtlasLayout>, Handle<Image>) {
+        (
+            self.texture_axeman_atlas_handle.0.clone(),
+            self.texture("textures/enemy_axe.png"),
+        )
+    }
+
+    fn texture(&self, path: &'static str) -> Handle<Image> {
+        self.asset_server.get_handle(path).unwrap_or_default()
+    }
+}
+
 fn spawn_enemies(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     time: Res<Time>,
     mut enemy_spawn_timer: ResMut<EnemySpawnTimer>,
     mut enemy_spawn_counter: ResMut<EnemySpawnCounter>,
     tile_query: Query<&Transform, With<BorderTile>>,
-    texture_archer_atlas_handle: Res<TextureArcherAtlasHandle>,
-    texture_axeman_atlas_handle: Res<TextureAxeAtlasHandle>,
+    enemy_textures: EnemyTextures,
+    settings: Res<Settings>,
 ) {
     let duration = enemy_spawn_timer.duration();
+    let difficulty = settings.difficulty;
 
-    if enemy_spawn_timer.tick(time.delta()).just_finished() {
+    if enemy_spawn_timer
+        .tick(time.delta().mul_f32(difficulty.spawn_rate()))
+        .just_finished()
+    {
         enemy_spawn_counter.0 = enemy_spawn_counter.0.wrapping_add(1);
 
         if enemy_spawn_counter.0 % 10 == 0 {
@@ -167,30 +217,34 @@ fn spawn_enemies(
             let translation = tile_transform.translation.truncate().extend(1.);
 
             //pick a random texture atlas handle between archer and axe
-            let (texture_atlas_handle, texture) = if rng.gen_bool(0.5) {
+            let (kind, (texture_atlas_handle, texture), projectile_kind) = if rng.gen_bool(0.5) {
                 (
-                    texture_archer_atlas_handle.0.clone(),
-                    asset_server
-                        .get_handle("textures/enemy_archer.png")
-                        .unwrap_or_default(),
+                    EnemyKind::Archer,
+                    enemy_textures.archer(),
+                    if rng.gen_bool(0.25) {
+                        ProjectileKind::FireArrow
+                    } else {
+                        ProjectileKind::Arrow
+                    },
                 )
             } else {
                 (
-                    texture_axeman_atlas_handle.0.clone(),
-                    asset_server
-                        .get_handle("textures/enemy_axe.png")
-                        .unwrap_or_default(),
+                    EnemyKind::Axeman,
+                    enemy_textures.axeman(),
+                    ProjectileKind::Net,
                 )
             };
 
             let mut enemy_entity_commands = commands.spawn(EnemyBundle {
-                attack_damage: AttackDamage(5),
+                attack_damage: AttackDamage((5. * difficulty.enemy_damage()).round() as i16),
                 attack_timer: AttackTimer::new(3.),
                 behavior: Behavior::FollowPlayer {
                     distance: TILE_SIZE.x * 6.,
                 },
                 hitpoints: ResourcePool::<Health>::new(1),
+                kind,
                 marker: Enemy,
+                projectile_kind,
                 range: Range(TILE_SIZE.x * 15.),
                 speed: Speed(2.),
                 animation_indices: AnimationIndices::new(4, 11),
@@ -232,8 +286,9 @@ fn handle_enemy_behavior(
             &mut SpriteAnimation,
             &mut AnimationIndices,
             &mut TextureAtlas,
+            Option<&Slowed>,
         ),
-        With<Enemy>,
+        (With<Enemy>, Without<Stunned>),
     >,
     player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
     sprite_animation_map: Res<SpriteAnimationMap>,
@@ -248,8 +303,11 @@ fn handle_enemy_behavior(
         mut sprite_orientation,
         mut animation_indices,
         mut texture_atlas,
+        slowed,
     ) in &mut enemy_query
     {
+        let enemy_speed = enemy_speed.0 * slowed.map_or(1., |slowed| slowed.factor);
+
         match enemy_behavior {
             &Behavior::FollowPlayer { distance } => {
                 let enemy_position = enemy_transform.translation.truncate();
@@ -258,8 +316,8 @@ fn handle_enemy_behavior(
                 let old_sprite_orientation = *sprite_orientation;
 
                 if enemy_position.distance(player_position) > distance {
-                    enemy_transform.translation.x += enemy_direction.x * enemy_speed.0;
-                    enemy_transform.translation.y += enemy_direction.y * enemy_speed.0;
+                    enemy_transform.translation.x += enemy_direction.x * enemy_speed;
+                    enemy_transform.translation.y += enemy_direction.y * enemy_speed;
 
                     // determine enemy quadrant based on enemy_direction
                     // and set sprite orientation accordingly
@@ -313,8 +371,15 @@ fn handle_enemy_behavior(
 fn handle_enemy_attacks(
     mut spawn_projectile_event_writer: EventWriter<SpawnProjectileEvent>,
     mut enemy_query: Query<
-        (Entity, &Transform, &mut AttackTimer, &Range, &AttackDamage),
-        With<Enemy>,
+        (
+            Entity,
+            &Transform,
+            &mut AttackTimer,
+            &Range,
+            &AttackDamage,
+            &ProjectileKind,
+        ),
+        (With<Enemy>, Without<Stunned>),
     >,
     player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
     time: Res<Time>,
@@ -322,8 +387,14 @@ fn handle_enemy_attacks(
     let player_transform = player_query.single();
     let player_position = player_transform.translation.truncate();
 
-    for (enemy_entity, enemy_transform, mut enemy_attack_timer, enemy_range, enemy_attack_damage) in
-        &mut enemy_query
+    for (
+        enemy_entity,
+        enemy_transform,
+        mut enemy_attack_timer,
+        enemy_range,
+        enemy_attack_damage,
+        &projectile_kind,
+    ) in &mut enemy_query
     {
         if enemy_attack_timer.tick(time.delta()).just_finished() {
             let enemy_position = enemy_transform.translation.truncate();
@@ -336,6 +407,7 @@ fn handle_enemy_attacks(
                     enemy_attack_damage.0,
                     direction,
                     emitter,
+                    projectile_kind,
                     enemy_position,
                     800.,
                 ));
@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{entity_cleanup, physics::Speed, playing, AppState, InGameState};
+
+use super::{
+    combat::EnemyKilledEvent,
+    enemy::EnemyKind,
+    fire_breath::{BreathEconomy, BreathWeaponRegistry},
+    pause::{
+        cancel_breath_charge, pause_audio, pause_physics, pause_time, resume_audio, resume_physics,
+        resume_time,
+    },
+    resource_pool::{Experience, Fire, Health, ResourcePool},
+    Player,
+};
+
+pub(super) struct ExperiencePlugin;
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, gain_experience.run_if(playing()));
+
+        app.add_systems(
+            OnEnter(InGameState::LevelUp),
+            (
+                pause_physics,
+                pause_time,
+                pause_audio,
+                cancel_breath_charge,
+                display_level_up_screen,
+            ),
+        );
+
+        app.add_systems(
+            PreUpdate,
+            handle_upgrade_button_interactions.run_if(in_state(InGameState::LevelUp)),
+        );
+
+        app.add_systems(
+            OnExit(InGameState::LevelUp),
+            (
+                resume_physics,
+                resume_time,
+                resume_audio,
+                entity_cleanup::<With<LevelUpEntity>>,
+            ),
+        );
+
+        app.add_systems(OnExit(AppState::InGame), reset_in_game_state);
+    }
+}
+
+/// The dragon's level during the current run, starting at 1.
+#[derive(Component, Deref)]
+pub struct ExperienceLevel {
+    #[deref]
+    level: u32,
+    /// Levels gained whose upgrade hasn't been picked yet.
+    pending_upgrades: u32,
+}
+
+impl Default for ExperienceLevel {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            pending_upgrades: 0,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum Upgrade {
+    MaxHealth,
+    FireCapacity,
+    BreathRadius,
+    Speed,
+    FireRegeneration,
+}
+
+impl Upgrade {
+    const ALL: [Self; 5] = [
+        Self::MaxHealth,
+        Self::FireCapacity,
+        Self::BreathRadius,
+        Self::Speed,
+        Self::FireRegeneration,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::MaxHealth => "Thick Scales",
+            Self::FireCapacity => "Deep Lungs",
+            Self::BreathRadius => "Wide Maw",
+            Self::Speed => "Strong Wings",
+            Self::FireRegeneration => "Hot Blood",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::MaxHealth => "+20 maximum health",
+            Self::FireCapacity => "+20 maximum fire",
+            Self::BreathRadius => "+20% breath radius",
+            Self::Speed => "+10% flight speed",
+            Self::FireRegeneration => "+1 fire regeneration",
+        }
+    }
+}
+
+#[derive(Component)]
+struct LevelUpEntity;
+
+fn gain_experience(
+    mut enemy_killed_event_reader: EventReader<EnemyKilledEvent>,
+    mut player_query: Query<(&mut ResourcePool<Experience>, &mut ExperienceLevel), With<Player>>,
+    mut next_state: ResMut<NextState<InGameState>>,
+) {
+    const MAX_EXPERIENCE_GROWTH: f32 = 1.3;
+
+    let Ok((mut experience, mut experience_level)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for event in enemy_killed_event_reader.read() {
+        let points = match event.kind {
+            EnemyKind::Archer | EnemyKind::Axeman => 10,
+            EnemyKind::Building => 25,
+        };
+        let total = experience.current() + points;
+
+        if total < experience.max() {
+            experience.add(points);
+            continue;
+        }
+
+        // Whatever goes over the maximum carries into the next level.
+        let old_max = experience.max();
+
+        experience.set_max((old_max as f32 * MAX_EXPERIENCE_GROWTH) as i16);
+        experience.set_current(total - old_max);
+        experience_level.level += 1;
+        experience_level.pending_upgrades += 1;
+    }
+
+    // Levels gained together are picked one after the other, coming back here in between.
+    if experience_level.pending_upgrades > 0 {
+        next_state.set(InGameState::LevelUp);
+    }
+}
+
+fn reset_in_game_state(mut next_state: ResMut<NextState<InGameState>>) {
+    next_state.set(InGameState::Running);
+}
+
+fn display_level_up_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_query: Query<&ExperienceLevel, With<Player>>,
+) {
+    const CHOICES: usize = 3;
+
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+    // The level this upgrade is for, which is behind the current one while several are pending.
+    let level = player_query.get_single().map_or(1, |experience_level| {
+        experience_level.level + 1 - experience_level.pending_upgrades.max(1)
+    });
+    let upgrades = Upgrade::ALL.choose_multiple(&mut rand::thread_rng(), CHOICES);
+
+    commands
+        .spawn((
+            LevelUpEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+                style: Style {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    row_gap: Val::Px(16.),
+                    width: Val::Percent(100.),
+                    ..default()
+                },
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                format!("Level {level}!"),
+                TextStyle {
+                    color: Color::GOLD,
+                    font: font.clone(),
+                    font_size: 64.,
+                },
+            ));
+
+            for &upgrade in upgrades {
+                builder
+                    .spawn((
+                        ButtonBundle {
+                            background_color: Color::ALICE_BLUE.into(),
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                flex_direction: FlexDirection::Column,
+                                padding: UiRect::all(Val::Px(8.)),
+                                width: Val::Px(320.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        upgrade,
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            upgrade.name(),
+                            TextStyle {
+                                color: Color::BLACK,
+                                font: font.clone(),
+                                font_size: 32.,
+                            },
+                        ));
+                        button.spawn(TextBundle::from_section(
+                            upgrade.description(),
+                            TextStyle {
+                                color: Color::DARK_GRAY,
+                                font: font.clone(),
+                                font_size: 20.,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_upgrade_button_interactions(
+    mut next_state: ResMut<NextState<InGameState>>,
+    mut player_query: Query<
+        (
+            &mut ResourcePool<Health>,
+            &mut ResourcePool<Fire>,
+            &mut Speed,
+            &mut ExperienceLevel,
+        ),
+        With<Player>,
+    >,
+    mut breath_economy: ResMut<BreathEconomy>,
+    mut breath_weapon_registry: ResMut<BreathWeaponRegistry>,
+    query: Query<(&Interaction, &Upgrade), (Changed<Interaction>, With<Button>)>,
+) {
+    const MAX_HEALTH_UPGRADE: i16 = 20;
+    const MAX_FIRE_UPGRADE: i16 = 20;
+    const BREATH_RADIUS_UPGRADE: f32 = 1.2;
+    const SPEED_UPGRADE: f32 = 1.1;
+
+    let Ok((mut hitpoints, mut fire, mut speed, mut experience_level)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    for (interaction, upgrade) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match upgrade {
+            Upgrade::MaxHealth => {
+                hitpoints.add_max(MAX_HEALTH_UPGRADE);
+                hitpoints.add(MAX_HEALTH_UPGRADE);
+            }
+            Upgrade::FireCapacity => {
+                fire.add_max(MAX_FIRE_UPGRADE);
+                fire.add(MAX_FIRE_UPGRADE);
+            }
+            Upgrade::BreathRadius => {
+                for breath_weapon in breath_weapon_registry.values_mut() {
+                    breath_weapon.radius *= BREATH_RADIUS_UPGRADE;
+                }
+            }
+            Upgrade::Speed => speed.0 *= SPEED_UPGRADE,
+            Upgrade::FireRegeneration => {
+                breath_economy.regeneration += 1;
+                breath_economy.idle_regeneration += 1;
+            }
+        }
+
+        experience_level.pending_upgrades = experience_level.pending_upgrades.saturating_sub(1);
+        next_state.set(InGameState::Running);
+        break;
+    }
+}
@@ -1,34 +1,70 @@
-use bevy::{prelude::*, render::view::RenderLayers};
-use bevy_kira_audio::{Audio, AudioChannel, AudioControl};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    render::view::RenderLayers,
+    utils::{HashMap, HashSet},
+};
 use bevy_particle_systems::*;
-use bevy_rapier2d::prelude::{Collider, CollisionGroups, Sensor};
+use bevy_rapier2d::prelude::{
+    Collider, ColliderDisabled, CollisionGroups, RapierContext, RigidBody, Sensor, Velocity,
+};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
 use crate::{
-    audio::DragonBreathChannel,
+    audio::{BreathAudio, DuckMusicEvent, PlaySoundEffectEvent},
     camera::{RenderLayer, YSorted},
-    playing,
+    playing, AppState,
 };
 
 use super::{
-    combat::ImpactDamage,
+    combat::{ImpactDamage, Lifetime, Projectile},
+    projectile::ProjectileMaterial,
     resource_pool::{Fire, ResourcePool},
-    InGameEntity, Player, BUILDING_GROUP, ENEMY_GROUP, FIRE_BREATH_GROUP,
+    score_system::{ScoreEvent, ScoreEventType},
+    statistics::RunStatistics,
+    Altitude, Enemy, InGameEntity, Player, BUILDING_GROUP, ENEMY_GROUP, FIRE_BREATH_GROUP,
+    PROJECTILE_GROUP,
 };
 
 pub(super) struct FireBreathPlugin;
 
 impl Plugin for FireBreathPlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<ChargedBreathEvent>();
         app.add_event::<SpawnFireBreathEvent>();
 
         app.add_plugins(ParticleSystemPlugin);
 
+        app.init_resource::<BreathEconomy>();
+        app.init_resource::<BreathWeaponRegistry>();
+        app.init_resource::<FireParticleAtlas>();
+
+        app.add_systems(
+            OnEnter(AppState::InGame),
+            (spawn_breath_emitter, reset_breath_upgrades),
+        );
+
         app.add_systems(
             FixedUpdate,
-            (consume_fire_breath_resource, restore_fire_breath_resource).run_if(playing()),
+            (
+                (
+                    consume_fire_breath_resource,
+                    restore_fire_breath_resource,
+                    update_overheat,
+                )
+                    .chain(),
+                burn_projectiles,
+                explode_fireballs,
+                recycle_fire_sensors,
+            )
+                .run_if(playing()),
         );
 
-        app.add_systems(Update, spawn_fire_breath.run_if(playing()));
+        app.add_systems(
+            Update,
+            (spawn_fire_breath, spawn_charged_breath).run_if(playing()),
+        );
 
         app.add_systems(
             PostUpdate,
@@ -40,12 +76,279 @@ impl Plugin for FireBreathPlugin {
 #[derive(Event)]
 pub struct SpawnFireBreathEvent {
     damage: i16,
+    direction: Vec2,
     position: Vec2,
 }
 
 impl SpawnFireBreathEvent {
-    pub fn new(damage: i16, position: Vec2) -> Self {
-        Self { damage, position }
+    pub fn new(damage: i16, direction: Vec2, position: Vec2) -> Self {
+        Self {
+            damage,
+            direction,
+            position,
+        }
+    }
+}
+
+/// Sent when a charged breath is released. `charge` goes from 0 to 1.
+#[derive(Event)]
+pub struct ChargedBreathEvent {
+    charge: f32,
+    direction: Vec2,
+    position: Vec2,
+}
+
+impl ChargedBreathEvent {
+    pub fn new(charge: f32, direction: Vec2, position: Vec2) -> Self {
+        Self {
+            charge,
+            direction,
+            position,
+        }
+    }
+}
+
+/// How much the player has charged up the next breath, from 0 to 1.
+#[derive(Component, Clone, Copy, Debug, Default, Deref, DerefMut)]
+pub struct BreathCharge(pub f32);
+
+/// Tunes how fast fire regenerates and how long an overheat lasts.
+#[derive(Resource)]
+pub struct BreathEconomy {
+    /// Fire restored every tick once the regeneration delay is over.
+    pub regeneration: i16,
+    /// Fire restored every tick while the dragon is hovering in place.
+    pub idle_regeneration: i16,
+    /// Seconds without breathing before fire starts regenerating.
+    pub regeneration_delay: f32,
+    /// Seconds the breath stays locked after the fire pool is drained.
+    pub overheat_cooldown: f32,
+}
+
+impl Default for BreathEconomy {
+    fn default() -> Self {
+        Self {
+            regeneration: 1,
+            idle_regeneration: 3,
+            regeneration_delay: 0.75,
+            overheat_cooldown: 3.0,
+        }
+    }
+}
+
+/// Keeps track of when the dragon last breathed and where it was on the previous tick.
+#[derive(Component, Default)]
+pub struct FireRegeneration {
+    delay: Timer,
+    last_position: Vec2,
+}
+
+/// Locks the breath until the timer finishes. Inserted when the fire pool is drained.
+#[derive(Component, Deref, DerefMut)]
+pub struct Overheated(Timer);
+
+impl Overheated {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BreathShape {
+    #[default]
+    Cone,
+    Beam,
+    Fireball,
+}
+
+impl BreathShape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cone => "Cone",
+            Self::Beam => "Beam",
+            Self::Fireball => "Fireball",
+        }
+    }
+}
+
+/// What the dragon breathes. Fire burns wooden projectiles, ice slows every enemy it touches.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum BreathElement {
+    #[default]
+    Fire,
+    Ice,
+}
+
+impl BreathElement {
+    /// Tint of the particles streaming out of the breath emitter.
+    fn stream_color(&self) -> ColorOverTime {
+        match self {
+            Self::Fire => ColorOverTime::Constant(Color::WHITE),
+            Self::Ice => ColorOverTime::Constant(Color::rgb(0.5, 0.8, 1.)),
+        }
+    }
+
+    /// Colors of the particles thrown out by charged breaths and fireball explosions.
+    fn burst_color(&self) -> ColorOverTime {
+        match self {
+            Self::Fire => (Color::YELLOW..Color::ORANGE_RED.with_a(0.)).into(),
+            Self::Ice => (Color::WHITE..Color::CYAN.with_a(0.)).into(),
+        }
+    }
+}
+
+/// The breath shapes the player can switch between during a run.
+#[derive(Component, Deref)]
+pub struct UnlockedBreathShapes(HashSet<BreathShape>);
+
+impl UnlockedBreathShapes {
+    pub fn new(shapes: impl IntoIterator<Item = BreathShape>) -> Self {
+        Self(shapes.into_iter().collect())
+    }
+}
+
+/// Describes how a breath shape behaves. Not every field applies to every shape.
+pub struct BreathWeapon {
+    pub damage_multiplier: i16,
+    /// Fire consumed every tick the breath is held, or by every shot for shapes fired at an
+    /// `interval`.
+    pub fire_cost: i16,
+    /// Minimum seconds between two emissions.
+    pub interval: f32,
+    pub length: f32,
+    /// Seconds the damage area stays behind after each emission.
+    pub linger: f32,
+    pub radius: f32,
+    /// Opening angle in radians.
+    pub spread: f32,
+    pub speed: f32,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct BreathWeaponRegistry(HashMap<BreathShape, BreathWeapon>);
+
+impl Default for BreathWeaponRegistry {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (
+                BreathShape::Cone,
+                BreathWeapon {
+                    damage_multiplier: 1,
+                    fire_cost: 1,
+                    interval: 0.,
+                    length: 60.,
+                    linger: 1.0,
+                    radius: 25.,
+                    spread: FRAC_PI_4,
+                    speed: 0.,
+                },
+            ),
+            (
+                BreathShape::Beam,
+                BreathWeapon {
+                    damage_multiplier: 2,
+                    fire_cost: 2,
+                    interval: 0.,
+                    length: 250.,
+                    linger: 0.25,
+                    radius: 8.,
+                    spread: 0.,
+                    speed: 0.,
+                },
+            ),
+            (
+                BreathShape::Fireball,
+                BreathWeapon {
+                    damage_multiplier: 3,
+                    fire_cost: 1,
+                    interval: 0.6,
+                    length: 0.,
+                    linger: 0.,
+                    radius: 60.,
+                    spread: 0.,
+                    speed: 400.,
+                },
+            ),
+        ]))
+    }
+}
+
+/// A projectile that explodes into a burst of fire when it hits something or runs out of time.
+#[derive(Component)]
+struct Fireball {
+    damage: i16,
+    element: BreathElement,
+    explosion_radius: f32,
+}
+
+/// The dragon's breath particles. There's only one of these and it's toggled on and off by
+/// adding or removing [`Playing`].
+#[derive(Component, Default)]
+struct BreathEmitter {
+    /// When the last shot of a shape fired at an interval left the emitter.
+    last_shot_seconds: f32,
+}
+
+/// The breath emitter along with the pool of fire sensors it leaves behind.
+#[derive(SystemParam)]
+struct BreathEmitters<'w, 's> {
+    emitter_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut BreathEmitter,
+            &'static mut Transform,
+            &'static mut ParticleSystem,
+            Has<Playing>,
+        ),
+    >,
+    idle_fire_sensor_query: Query<'w, 's, Entity, (With<FireSensor>, With<ColliderDisabled>)>,
+}
+
+/// A damage area left behind by the breath. These are pooled: when the timer finishes the
+/// collider is disabled and the entity waits to be reused by the next emission.
+#[derive(Component, Deref, DerefMut)]
+struct FireSensor(Timer);
+
+/// The atlas layout shared by every fire particle, so it's only added to the assets once.
+#[derive(Resource)]
+struct FireParticleAtlas(Handle<TextureAtlasLayout>);
+
+impl FromWorld for FireParticleAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let texture_atlas_layout_fire =
+            TextureAtlasLayout::from_grid(Vec2::new(40., 40.), 2, 1, None, None);
+
+        Self(
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(texture_atlas_layout_fire),
+        )
+    }
+}
+
+/// Looks up the animated texture of the fire particles.
+#[derive(SystemParam)]
+struct FireParticleTexture<'w> {
+    asset_server: Res<'w, AssetServer>,
+    fire_particle_atlas: Res<'w, FireParticleAtlas>,
+}
+
+impl FireParticleTexture<'_> {
+    fn get(&self) -> ParticleTexture {
+        ParticleTexture::TextureAtlas {
+            atlas: self.fire_particle_atlas.0.clone(),
+            index: AtlasIndex::Animated(AnimatedIndex {
+                indices: vec![0, 1],
+                time_step: 0.2,
+                step_offset: 0,
+            }),
+            texture: self
+                .asset_server
+                .get_handle("textures/fire_anim.png")
+                .unwrap_or_default(),
+        }
     }
 }
 
@@ -59,69 +362,503 @@ struct FireBreathBundle {
     pub sensor: Sensor,
 }
 
+/// Level-up upgrades only last for one run.
+fn reset_breath_upgrades(
+    mut breath_economy: ResMut<BreathEconomy>,
+    mut breath_weapon_registry: ResMut<BreathWeaponRegistry>,
+) {
+    *breath_economy = BreathEconomy::default();
+    *breath_weapon_registry = BreathWeaponRegistry::default();
+}
+
+fn spawn_breath_emitter(mut commands: Commands) {
+    commands.spawn((
+        BreathEmitter::default(),
+        ParticleSystemBundle {
+            particle_system: ParticleSystem {
+                max_particles: 10_000,
+                looping: true,
+                system_duration_seconds: 1.0,
+                ..ParticleSystem::default()
+            },
+            ..ParticleSystemBundle::default()
+        },
+        RenderLayers::layer(RenderLayer::Ground.into()),
+        InGameEntity,
+        YSorted,
+    ));
+}
+
 fn spawn_fire_breath(
     mut commands: Commands,
     mut spawn_fire_breath_event_reader: EventReader<SpawnFireBreathEvent>,
-    asset_server: Res<AssetServer>,
-    player_query: Query<&ResourcePool<Fire>, With<Player>>,
+    mut breath_emitters: BreathEmitters,
+    breath_weapon_registry: Res<BreathWeaponRegistry>,
+    fire_particle_texture: FireParticleTexture,
+    player_query: Query<
+        (&ResourcePool<Fire>, &BreathShape, &BreathElement, &Altitude),
+        With<Player>,
+    >,
+    time: Res<Time>,
 ) {
-    let Ok(fire_resource_pool) = player_query.get_single() else {
+    const FIREBALL_LIFETIME_SECONDS: f32 = 1.5;
+
+    let Ok((
+        emitter_entity,
+        mut breath_emitter,
+        mut emitter_transform,
+        mut emitter,
+        emitter_playing,
+    )) = breath_emitters.emitter_query.get_single_mut()
+    else {
         return;
     };
+    let Ok((fire_resource_pool, breath_shape, breath_element, altitude)) =
+        player_query.get_single()
+    else {
+        return;
+    };
+    let breath_weapon = breath_weapon_registry
+        .get(breath_shape)
+        .filter(|_| !fire_resource_pool.is_empty() && !spawn_fire_breath_event_reader.is_empty());
+
+    let Some(breath_weapon) = breath_weapon else {
+        spawn_fire_breath_event_reader.clear();
+
+        if emitter_playing {
+            commands.entity(emitter_entity).remove::<Playing>();
+        }
 
-    if fire_resource_pool.is_empty() {
         return;
-    }
+    };
 
-    let fire_texture = asset_server
-        .get_handle("textures/fire_anim.png")
-        .unwrap_or_default();
-    let texture_atlas_layout_fire =
-        TextureAtlasLayout::from_grid(Vec2::new(40., 40.), 2, 1, None, None);
-    let texture_atlas_handle_fire = asset_server.add(texture_atlas_layout_fire);
-    let animated_index: AtlasIndex = AtlasIndex::Animated(AnimatedIndex {
-        indices: vec![0, 1],
-        time_step: 0.2,
-        step_offset: 0,
-    });
+    let particle_texture = fire_particle_texture.get();
+    let mut idle_fire_sensors = breath_emitters.idle_fire_sensor_query.iter();
+    let mut emitting = false;
 
-    for &SpawnFireBreathEvent { damage, position } in spawn_fire_breath_event_reader.read() {
-        let mut fire_breath_entity_commands = commands.spawn(FireBreathBundle {
-            marker: Fire,
-            particle_system: ParticleSystemBundle {
-                transform: Transform::from_translation(position.extend(10.0)),
-                particle_system: ParticleSystem {
-                    max_particles: 10_000,
-                    texture: ParticleTexture::TextureAtlas {
-                        atlas: texture_atlas_handle_fire.clone(),
-                        index: animated_index.clone(),
-                        texture: fire_texture.clone(),
-                    },
-                    spawn_rate_per_second: 5.0.into(),
+    for &SpawnFireBreathEvent {
+        damage,
+        direction,
+        position,
+    } in spawn_fire_breath_event_reader.read()
+    {
+        let damage = altitude.breath_damage(damage * breath_weapon.damage_multiplier);
+        let linger = breath_weapon.linger * altitude.breath_strength();
+        let direction_angle = direction.y.atan2(direction.x);
+
+        match breath_shape {
+            BreathShape::Cone => {
+                let sensors = [-0.5, 0., 0.5]
+                    .into_iter()
+                    .map(|side| {
+                        let offset = Vec2::from_angle(side * breath_weapon.spread)
+                            .rotate(direction)
+                            * breath_weapon.length
+                            * 0.5;
+                        (offset, 0., Collider::ball(breath_weapon.radius))
+                    })
+                    .collect();
+
+                emitter_transform.translation = position.extend(10.0);
+                *emitter = ParticleSystem {
+                    texture: particle_texture.clone(),
+                    spawn_rate_per_second: 300.0.into(),
+                    emitter_shape: EmitterShape::CircleSegment(CircleSegment {
+                        opening_angle: breath_weapon.spread,
+                        direction_angle,
+                        radius: 0.0.into(),
+                    }),
                     initial_speed: JitteredValue::jittered(3.0, -1.0..1.0),
                     lifetime: JitteredValue::jittered(4.0, -1.0..1.0),
-                    looping: false,
-                    despawn_on_finish: true,
-                    system_duration_seconds: 1.0,
-                    ..ParticleSystem::default()
+                    color: breath_element.stream_color(),
+                    rescale_texture: None,
+                    ..emitter.clone()
+                };
+                emitting = true;
+
+                activate_fire_sensor(
+                    &mut commands,
+                    idle_fire_sensors.next(),
+                    Collider::compound(sensors),
+                    damage,
+                    position,
+                    linger,
+                );
+            }
+            BreathShape::Beam => {
+                let beam_center = position + direction * breath_weapon.length * 0.5;
+
+                emitter_transform.translation = beam_center.extend(10.0);
+                *emitter = ParticleSystem {
+                    texture: particle_texture.clone(),
+                    spawn_rate_per_second: 300.0.into(),
+                    emitter_shape: EmitterShape::line(
+                        breath_weapon.length,
+                        direction_angle - FRAC_PI_2,
+                    ),
+                    initial_speed: JitteredValue::jittered(3.0, -1.0..1.0),
+                    lifetime: JitteredValue::jittered(0.5, -0.2..0.2),
+                    color: breath_element.stream_color(),
+                    rescale_texture: Some(Vec2::splat(breath_weapon.radius * 2.)),
+                    ..emitter.clone()
+                };
+                emitting = true;
+
+                activate_fire_sensor(
+                    &mut commands,
+                    idle_fire_sensors.next(),
+                    Collider::compound(vec![(
+                        Vec2::ZERO,
+                        direction_angle,
+                        Collider::cuboid(breath_weapon.length * 0.5, breath_weapon.radius),
+                    )]),
+                    damage,
+                    beam_center,
+                    linger,
+                );
+            }
+            BreathShape::Fireball => {
+                if time.elapsed_seconds() - breath_emitter.last_shot_seconds
+                    < breath_weapon.interval
+                {
+                    continue;
+                }
+
+                breath_emitter.last_shot_seconds = time.elapsed_seconds();
+
+                commands.spawn((
+                    Fireball {
+                        damage,
+                        element: *breath_element,
+                        explosion_radius: breath_weapon.radius,
+                    },
+                    ParticleSystemBundle {
+                        transform: Transform::from_translation(position.extend(10.0)),
+                        particle_system: ParticleSystem {
+                            max_particles: 200,
+                            texture: particle_texture.clone(),
+                            spawn_rate_per_second: 30.0.into(),
+                            initial_speed: JitteredValue::jittered(10.0, -5.0..5.0),
+                            lifetime: JitteredValue::jittered(0.3, -0.1..0.1),
+                            color: breath_element.stream_color(),
+                            looping: true,
+                            system_duration_seconds: 1.0,
+                            ..ParticleSystem::default()
+                        },
+                        ..ParticleSystemBundle::default()
+                    },
+                    Collider::ball(12.),
+                    CollisionGroups::new(FIRE_BREATH_GROUP, BUILDING_GROUP | ENEMY_GROUP),
+                    Lifetime::new(FIREBALL_LIFETIME_SECONDS),
+                    RenderLayers::layer(RenderLayer::Sky.into()),
+                    RigidBody::KinematicVelocityBased,
+                    Sensor,
+                    Velocity::linear(direction * breath_weapon.speed),
+                    InGameEntity,
+                    Playing,
+                ));
+            }
+        }
+    }
+
+    if emitting && !emitter_playing {
+        commands.entity(emitter_entity).insert(Playing);
+    } else if !emitting && emitter_playing {
+        commands.entity(emitter_entity).remove::<Playing>();
+    }
+}
+
+/// Reuses an idle fire sensor if there's one, otherwise grows the pool with a new one.
+fn activate_fire_sensor(
+    commands: &mut Commands,
+    idle_fire_sensor: Option<Entity>,
+    collider: Collider,
+    damage: i16,
+    position: Vec2,
+    seconds: f32,
+) {
+    let fire_sensor = (
+        collider,
+        FireSensor(Timer::from_seconds(seconds, TimerMode::Once)),
+        ImpactDamage(damage),
+        TransformBundle::from_transform(Transform::from_translation(position.extend(10.0))),
+    );
+
+    match idle_fire_sensor {
+        Some(entity) => {
+            commands
+                .entity(entity)
+                .insert(fire_sensor)
+                .remove::<ColliderDisabled>();
+        }
+        None => {
+            commands.spawn((
+                fire_sensor,
+                CollisionGroups::new(
+                    FIRE_BREATH_GROUP,
+                    BUILDING_GROUP | ENEMY_GROUP | PROJECTILE_GROUP,
+                ),
+                Fire,
+                InGameEntity,
+                Sensor,
+            ));
+        }
+    }
+}
+
+fn recycle_fire_sensors(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut FireSensor), Without<ColliderDisabled>>,
+    time: Res<Time>,
+) {
+    for (entity, mut fire_sensor) in &mut query {
+        if fire_sensor.tick(time.delta()).finished() {
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+    }
+}
+
+fn spawn_charged_breath(
+    mut commands: Commands,
+    mut charged_breath_event_reader: EventReader<ChargedBreathEvent>,
+    mut duck_music_event_writer: EventWriter<DuckMusicEvent>,
+    mut fire_spender: FireSpender,
+    player_query: Query<(&Altitude, &BreathElement), With<Player>>,
+    asset_server: Res<AssetServer>,
+) {
+    const MAX_FIRE_COST: f32 = 50.;
+    const MIN_RADIUS: f32 = 30.;
+    const MAX_RADIUS: f32 = 120.;
+    const MIN_DAMAGE: f32 = 2.;
+    const MAX_DAMAGE: f32 = 10.;
+    // Charges at least this full are loud enough to duck the music.
+    const DUCKING_CHARGE: f32 = 0.75;
+
+    let Ok((altitude, breath_element)) = player_query.get_single() else {
+        return;
+    };
+
+    for &ChargedBreathEvent {
+        charge,
+        direction,
+        position,
+    } in charged_breath_event_reader.read()
+    {
+        // A charge the player can't afford is scaled down to whatever fire is left.
+        let affordable_charge = (fire_spender.current() as f32 / MAX_FIRE_COST).min(1.);
+        let charge = charge.clamp(0., affordable_charge);
+        let fire_cost = (charge * MAX_FIRE_COST).ceil() as i16;
+
+        if fire_cost == 0 {
+            continue;
+        }
+
+        fire_spender.spend(&mut commands, fire_cost);
+
+        if charge >= DUCKING_CHARGE {
+            duck_music_event_writer.send(DuckMusicEvent::new(0.4, 0.5));
+        }
+
+        let radius = MIN_RADIUS + (MAX_RADIUS - MIN_RADIUS) * charge;
+        let damage = altitude
+            .breath_damage((MIN_DAMAGE + (MAX_DAMAGE - MIN_DAMAGE) * charge).round() as i16);
+        let fire_texture = asset_server
+            .get_handle("textures/fire_anim_washed.png")
+            .unwrap_or_default();
+
+        spawn_fire_breath_emitter(
+            &mut commands,
+            FireBreathBundle {
+                marker: Fire,
+                particle_system: ParticleSystemBundle {
+                    transform: Transform::from_translation(
+                        (position + direction * radius).extend(10.0),
+                    ),
+                    particle_system: ParticleSystem {
+                        max_particles: 200,
+                        texture: ParticleTexture::Sprite(fire_texture),
+                        spawn_rate_per_second: 0.0.into(),
+                        bursts: vec![ParticleBurst::new(0.0, (40. + 120. * charge) as usize)],
+                        initial_speed: JitteredValue::jittered(radius * 2., -20.0..20.0),
+                        lifetime: JitteredValue::jittered(0.5, -0.2..0.2),
+                        color: breath_element.burst_color(),
+                        looping: false,
+                        despawn_on_finish: true,
+                        system_duration_seconds: 0.3,
+                        ..ParticleSystem::default()
+                    },
+                    ..ParticleSystemBundle::default()
                 },
-                ..ParticleSystemBundle::default()
+                render_layers: RenderLayers::layer(RenderLayer::Ground.into()),
+                sensor: Sensor,
+                collider: Collider::ball(radius),
+                damage: ImpactDamage(damage),
             },
-            render_layers: RenderLayers::layer(RenderLayer::Ground.into()),
-            sensor: Sensor,
-            collider: Collider::ball(25.0),
-            damage: ImpactDamage(damage),
-        });
-
-        fire_breath_entity_commands.insert((
-            CollisionGroups::new(FIRE_BREATH_GROUP, BUILDING_GROUP | ENEMY_GROUP),
-            InGameEntity,
-            Playing,
-            YSorted,
+        );
+    }
+}
+
+fn spawn_fire_breath_emitter(commands: &mut Commands, fire_breath_bundle: FireBreathBundle) {
+    let mut fire_breath_entity_commands = commands.spawn(fire_breath_bundle);
+
+    fire_breath_entity_commands.insert((
+        CollisionGroups::new(
+            FIRE_BREATH_GROUP,
+            BUILDING_GROUP | ENEMY_GROUP | PROJECTILE_GROUP,
+        ),
+        InGameEntity,
+        Playing,
+        YSorted,
+    ));
+}
+
+fn explode_fireballs(
+    mut commands: Commands,
+    mut duck_music_event_writer: EventWriter<DuckMusicEvent>,
+    mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+    asset_server: Res<AssetServer>,
+    fireball_query: Query<(Entity, &Fireball, &Transform, &Lifetime)>,
+    enemy_query: Query<(), With<Enemy>>,
+    rapier_context: Res<RapierContext>,
+) {
+    for (entity, fireball, transform, lifetime) in &fireball_query {
+        let hit_enemy = rapier_context.intersection_pairs_with(entity).any(
+            |(entity1, entity2, intersecting)| {
+                let other_entity = if entity1 == entity { entity2 } else { entity1 };
+                intersecting && enemy_query.contains(other_entity)
+            },
+        );
+
+        if !hit_enemy && !lifetime.finished() {
+            continue;
+        }
+
+        commands.entity(entity).despawn_recursive();
+        duck_music_event_writer.send(DuckMusicEvent::new(0.5, 0.3));
+        play_sound_effect_event_writer.send(PlaySoundEffectEvent::new(
+            "explosion.ogg",
+            None,
+            Some(*transform),
         ));
+
+        let fire_texture = asset_server
+            .get_handle("textures/fire_anim_washed.png")
+            .unwrap_or_default();
+
+        spawn_fire_breath_emitter(
+            &mut commands,
+            FireBreathBundle {
+                marker: Fire,
+                particle_system: ParticleSystemBundle {
+                    transform: Transform::from_translation(transform.translation),
+                    particle_system: ParticleSystem {
+                        max_particles: 100,
+                        texture: ParticleTexture::Sprite(fire_texture),
+                        spawn_rate_per_second: 0.0.into(),
+                        bursts: vec![ParticleBurst::new(0.0, 60)],
+                        initial_speed: JitteredValue::jittered(
+                            fireball.explosion_radius,
+                            -20.0..20.0,
+                        ),
+                        lifetime: JitteredValue::jittered(0.5, -0.2..0.2),
+                        color: fireball.element.burst_color(),
+                        looping: false,
+                        despawn_on_finish: true,
+                        system_duration_seconds: 0.25,
+                        ..ParticleSystem::default()
+                    },
+                    ..ParticleSystemBundle::default()
+                },
+                render_layers: RenderLayers::layer(RenderLayer::Ground.into()),
+                sensor: Sensor,
+                collider: Collider::ball(fireball.explosion_radius),
+                damage: ImpactDamage(fireball.damage),
+            },
+        );
+    }
+}
+
+/// Wooden projectiles caught in the fire breath burn up, stone and metal ones fly through.
+fn burn_projectiles(
+    mut commands: Commands,
+    mut score_event_writer: EventWriter<ScoreEvent>,
+    asset_server: Res<AssetServer>,
+    fire_query: Query<Entity, With<Fire>>,
+    player_query: Query<&BreathElement, With<Player>>,
+    projectile_query: Query<(&Transform, &ProjectileMaterial), With<Projectile>>,
+    rapier_context: Res<RapierContext>,
+) {
+    const BURNED_PROJECTILE_POINTS: i32 = 2;
+
+    if player_query.get_single().map_or(true, |breath_element| {
+        *breath_element != BreathElement::Fire
+    }) {
+        return;
+    }
+
+    let mut burned_projectiles = HashSet::new();
+
+    for entity in &fire_query {
+        for (entity1, entity2, intersecting) in rapier_context.intersection_pairs_with(entity) {
+            let other_entity = if entity1 == entity { entity2 } else { entity1 };
+
+            if !intersecting {
+                continue;
+            }
+
+            let Ok((projectile_transform, projectile_material)) =
+                projectile_query.get(other_entity)
+            else {
+                continue;
+            };
+
+            if projectile_material.is_flammable() && burned_projectiles.insert(other_entity) {
+                commands.entity(other_entity).despawn_recursive();
+                score_event_writer.send(ScoreEvent::new(
+                    BURNED_PROJECTILE_POINTS,
+                    ScoreEventType::AddPoints,
+                    Some(projectile_transform.translation.truncate()),
+                ));
+                spawn_embers(
+                    &mut commands,
+                    &asset_server,
+                    projectile_transform.translation.truncate(),
+                );
+            }
+        }
     }
 }
 
+fn spawn_embers(commands: &mut Commands, asset_server: &AssetServer, position: Vec2) {
+    let ember_texture = asset_server
+        .get_handle("textures/fire_anim_washed.png")
+        .unwrap_or_default();
+
+    commands.spawn((
+        ParticleSystemBundle {
+            transform: Transform::from_translation(position.extend(10.0)),
+            particle_system: ParticleSystem {
+                max_particles: 12,
+                texture: ParticleTexture::Sprite(ember_texture),
+                rescale_texture: Some(Vec2::splat(4.)),
+                spawn_rate_per_second: 0.0.into(),
+                bursts: vec![ParticleBurst::new(0.0, 12)],
+                initial_speed: JitteredValue::jittered(40.0, -20.0..20.0),
+                lifetime: JitteredValue::jittered(0.4, -0.2..0.2),
+                color: (Color::ORANGE..Color::ORANGE_RED.with_a(0.)).into(),
+                looping: false,
+                despawn_on_finish: true,
+                system_duration_seconds: 0.5,
+                ..ParticleSystem::default()
+            },
+            ..ParticleSystemBundle::default()
+        },
+        RenderLayers::layer(RenderLayer::Sky.into()),
+        InGameEntity,
+        Playing,
+    ));
+}
+
 fn update_fire_particles_render_layers(
     mut commands: Commands,
     query: Query<(Entity, &Particle)>,
@@ -135,40 +872,123 @@ fn update_fire_particles_render_layers(
 }
 
 fn consume_fire_breath_resource(
-    mut player_query: Query<&mut ResourcePool<Fire>, With<Player>>,
+    mut commands: Commands,
+    mut fire_spender: FireSpender,
+    player_query: Query<&BreathShape, With<Player>>,
     spawn_fire_breath_event_reader: EventReader<SpawnFireBreathEvent>,
-    dragon_breath_audio_channel: Res<AudioChannel<DragonBreathChannel>>,
-    audio: Res<Audio>,
-    asset_server: Res<AssetServer>,
+    launched_fireball_query: Query<(), Added<Fireball>>,
+    mut breath_audio: BreathAudio,
+    breath_weapon_registry: Res<BreathWeaponRegistry>,
 ) {
-    const FIRE_BREATH_CONSUMPTION_RATIO: i16 = 1;
+    let Some(breath_weapon) = player_query
+        .get_single()
+        .ok()
+        .and_then(|breath_shape| breath_weapon_registry.get(breath_shape))
+    else {
+        return;
+    };
+    // Shapes fired at an interval pay for the shots they actually launched, not for every tick
+    // the breath is held between them.
+    let fire_cost = if breath_weapon.interval > 0. {
+        breath_weapon.fire_cost * launched_fireball_query.iter().count() as i16
+    } else if !spawn_fire_breath_event_reader.is_empty() {
+        breath_weapon.fire_cost
+    } else {
+        0
+    };
 
-    if !spawn_fire_breath_event_reader.is_empty() {
-        let mut fire_resource_pool = player_query.single_mut();
+    if fire_cost > 0 && fire_spender.spend(&mut commands, fire_cost) {
+        breath_audio.end();
+    }
+}
+
+/// The dragon's fire along with what draining it affects.
+#[derive(SystemParam)]
+struct FireSpender<'w, 's> {
+    player_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut ResourcePool<Fire>,
+            &'static mut FireRegeneration,
+        ),
+        With<Player>,
+    >,
+    run_statistics: ResMut<'w, RunStatistics>,
+    breath_economy: Res<'w, BreathEconomy>,
+}
+
+impl FireSpender<'_, '_> {
+    fn current(&self) -> i16 {
+        self.player_query
+            .get_single()
+            .map_or(0, |(_, fire_resource_pool, _)| fire_resource_pool.current())
+    }
 
-        fire_resource_pool.subtract(FIRE_BREATH_CONSUMPTION_RATIO);
+    /// Subtracts `fire_cost` from the pool, postpones regeneration and overheats the dragon
+    /// if the pool was drained. Returns whether the pool is empty.
+    fn spend(&mut self, commands: &mut Commands, fire_cost: i16) -> bool {
+        let Ok((player_entity, mut fire_resource_pool, mut fire_regeneration)) =
+            self.player_query.get_single_mut()
+        else {
+            return false;
+        };
+
+        fire_resource_pool.subtract(fire_cost);
+        self.run_statistics.fire_spent += fire_cost as u32;
+        fire_regeneration.delay =
+            Timer::from_seconds(self.breath_economy.regeneration_delay, TimerMode::Once);
 
         if fire_resource_pool.is_empty() {
-            audio.play(
-                asset_server
-                    .get_handle("sfx/breathend.ogg")
-                    .unwrap_or_default(),
-            );
-            dragon_breath_audio_channel.stop();
+            commands
+                .entity(player_entity)
+                .insert(Overheated::new(self.breath_economy.overheat_cooldown));
         }
+
+        fire_resource_pool.is_empty()
     }
 }
 
 fn restore_fire_breath_resource(
-    mut player_query: Query<&mut ResourcePool<Fire>, With<Player>>,
-    spawn_fire_breath_event_reader: EventReader<SpawnFireBreathEvent>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut player_query: Query<
+        (
+            &mut ResourcePool<Fire>,
+            &mut FireRegeneration,
+            &BreathCharge,
+            &Transform,
+        ),
+        With<Player>,
+    >,
+    breath_economy: Res<BreathEconomy>,
+    time: Res<Time>,
 ) {
-    const FIRE_BREATH_RESTORATION_RATIO: i16 = 1;
+    let (mut fire_resource_pool, mut fire_regeneration, breath_charge, player_transform) =
+        player_query.single_mut();
+    let player_position = player_transform.translation.truncate();
+    let idle = player_position == fire_regeneration.last_position;
+
+    fire_regeneration.last_position = player_position;
+
+    if !fire_regeneration.delay.tick(time.delta()).finished() || breath_charge.0 > 0. {
+        return;
+    }
 
-    if spawn_fire_breath_event_reader.is_empty() && !mouse_input.pressed(MouseButton::Left) {
-        let mut fire_resource_pool = player_query.single_mut();
+    fire_resource_pool.add(if idle {
+        breath_economy.idle_regeneration
+    } else {
+        breath_economy.regeneration
+    });
+}
 
-        fire_resource_pool.add(FIRE_BREATH_RESTORATION_RATIO);
+fn update_overheat(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Overheated)>,
+    time: Res<Time>,
+) {
+    for (entity, mut overheated) in &mut query {
+        if overheated.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Overheated>();
+        }
     }
 }
@@ -0,0 +1,163 @@
+use bevy::{prelude::*, render::view::RenderLayers, utils::HashMap};
+
+use crate::{camera::RenderLayer, playing};
+
+use super::{combat::DamageEvent, InGameEntity};
+
+pub(super) struct FloatingTextPlugin;
+
+impl Plugin for FloatingTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnFloatingTextEvent>();
+
+        app.init_resource::<PendingDamageNumbers>();
+
+        app.add_systems(
+            Update,
+            (
+                collect_damage_numbers,
+                flush_damage_numbers,
+                spawn_floating_text,
+                update_floating_text,
+            )
+                .chain()
+                .run_if(playing()),
+        );
+    }
+}
+
+#[derive(Event)]
+pub struct SpawnFloatingTextEvent {
+    color: Color,
+    position: Vec2,
+    text: String,
+}
+
+impl SpawnFloatingTextEvent {
+    pub fn new(text: impl Into<String>, position: Vec2, color: Color) -> Self {
+        let text = text.into();
+        Self {
+            color,
+            position,
+            text,
+        }
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct FloatingText(Timer);
+
+/// Damage dealt to each target is accumulated for a short while so that
+/// damage over time shows up as a single readable number.
+#[derive(Resource)]
+struct PendingDamageNumbers {
+    damage: HashMap<Entity, (i16, Vec2)>,
+    timer: Timer,
+}
+
+impl Default for PendingDamageNumbers {
+    fn default() -> Self {
+        Self {
+            damage: HashMap::default(),
+            timer: Timer::from_seconds(0.25, TimerMode::Repeating),
+        }
+    }
+}
+
+fn collect_damage_numbers(
+    mut damage_event_reader: EventReader<DamageEvent>,
+    mut pending_damage_numbers: ResMut<PendingDamageNumbers>,
+) {
+    for &DamageEvent {
+        amount,
+        position,
+        target,
+    } in damage_event_reader.read()
+    {
+        let entry = pending_damage_numbers
+            .damage
+            .entry(target)
+            .or_insert((0, position));
+        entry.0 += amount;
+        entry.1 = position;
+    }
+}
+
+fn flush_damage_numbers(
+    mut pending_damage_numbers: ResMut<PendingDamageNumbers>,
+    mut spawn_floating_text_event_writer: EventWriter<SpawnFloatingTextEvent>,
+    time: Res<Time>,
+) {
+    if pending_damage_numbers
+        .timer
+        .tick(time.delta())
+        .just_finished()
+    {
+        for (_, (amount, position)) in pending_damage_numbers.damage.drain() {
+            spawn_floating_text_event_writer.send(SpawnFloatingTextEvent::new(
+                amount.to_string(),
+                position,
+                Color::WHITE,
+            ));
+        }
+    }
+}
+
+fn spawn_floating_text(
+    mut commands: Commands,
+    mut spawn_floating_text_event_reader: EventReader<SpawnFloatingTextEvent>,
+    asset_server: Res<AssetServer>,
+) {
+    const FLOATING_TEXT_SECONDS: f32 = 1.0;
+
+    let font = asset_server
+        .get_handle("fonts/Prince Valiant.ttf")
+        .unwrap_or_default();
+
+    for SpawnFloatingTextEvent {
+        color,
+        position,
+        text,
+    } in spawn_floating_text_event_reader.read()
+    {
+        commands.spawn((
+            FloatingText(Timer::from_seconds(FLOATING_TEXT_SECONDS, TimerMode::Once)),
+            InGameEntity,
+            RenderLayers::layer(RenderLayer::Sky.into()),
+            Text2dBundle {
+                text: Text::from_section(
+                    text.clone(),
+                    TextStyle {
+                        color: *color,
+                        font: font.clone(),
+                        font_size: 20.,
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(10.)),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_floating_text(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut FloatingText, &mut Transform, &mut Text)>,
+    time: Res<Time>,
+) {
+    const RISE_SPEED: f32 = 40.;
+
+    for (entity, mut floating_text, mut transform, mut text) in &mut query {
+        if floating_text.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        transform.translation.y += RISE_SPEED * time.delta_seconds();
+
+        let alpha = floating_text.fraction_remaining();
+        for section in &mut text.sections {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
@@ -1,13 +1,12 @@
 use bevy::prelude::*;
 
-use crate::{
-    audio::{PlayMusicEvent, PlaybackSettings},
-    entity_cleanup, playing, AppState,
-};
+use crate::{audio::PlayMusicEvent, entity_cleanup, lair::Hoard, playing, AppState};
 
 use super::{
+    enemy::EnemyKind,
     resource_pool::{Health, ResourcePool},
     score_system::Score,
+    statistics::RunStatistics,
     InGameEntity, Player,
 };
 
@@ -67,7 +66,45 @@ fn check_game_over_condition(
     }
 }
 
-fn display_game_over_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn display_game_over_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut hoard: ResMut<Hoard>,
+    player_query: Query<&Score, With<Player>>,
+    run_statistics: Res<RunStatistics>,
+) {
+    const SCORE_PER_GOLD: i32 = 10;
+
+    // The run's score is banked into the hoard as gold to spend in the Lair.
+    let gold = player_query
+        .get_single()
+        .map_or(0, |score| (score.current() / SCORE_PER_GOLD).max(0) as u32);
+
+    hoard.gold += gold;
+    hoard.save();
+
+    let time_survived = run_statistics.time_survived.as_secs();
+    let statistics = format!(
+        "Time survived: {:02}:{:02}\n\
+         Archers slain: {} - Axemen slain: {}\n\
+         Buildings destroyed: {}\n\
+         Damage dealt: {} - Damage taken: {}\n\
+         Fire spent: {} - Power-ups collected: {}\n\
+         Longest streak: x {}\n\
+         Gold added to hoard: {}",
+        time_survived / 60,
+        time_survived % 60,
+        run_statistics.kills_of(EnemyKind::Archer),
+        run_statistics.kills_of(EnemyKind::Axeman),
+        run_statistics.buildings_destroyed,
+        run_statistics.damage_dealt,
+        run_statistics.damage_taken,
+        run_statistics.fire_spent,
+        run_statistics.powerups_collected,
+        run_statistics.longest_multiplier_streak,
+        gold,
+    );
+
     commands
         .spawn((
             GameOverBackground,
@@ -122,6 +159,28 @@ fn display_game_over_screen(mut commands: Commands, asset_server: Res<AssetServe
                 },
             ));
 
+            builder.spawn((
+                GameOverText,
+                TextBundle {
+                    style: Style {
+                        margin: UiRect::vertical(Val::Px(16.)),
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        statistics,
+                        TextStyle {
+                            color: Color::WHITE.with_a(0.),
+                            font: asset_server
+                                .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+                                .unwrap_or_default(),
+                            font_size: 20.,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    ..default()
+                },
+            ));
+
             builder
                 .spawn((
                     ButtonBundle {
@@ -184,14 +243,7 @@ fn fade_in_text(
 }
 
 fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
-    play_music_event_writer.send(PlayMusicEvent::new(
-        "theme3.ogg",
-        Some(PlaybackSettings {
-            volume: 0.25,
-            ..default()
-        }),
-        None,
-    ));
+    play_music_event_writer.send(PlayMusicEvent::new_with_defaults("theme3.ogg"));
 }
 
 fn handle_game_over_button_interactions(
@@ -3,7 +3,10 @@ use bevy::{prelude::*, text::BreakLineOn};
 use crate::{playing, AppState};
 
 use super::{
-    resource_pool::{Fire, Health, ResourcePool},
+    ability::{Abilities, AbilityKind},
+    experience::ExperienceLevel,
+    fire_breath::{BreathCharge, BreathShape, Overheated},
+    resource_pool::{Experience, Fire, Health, ResourcePool},
     score_system::Score,
     InGameEntity, Player,
 };
@@ -11,6 +14,7 @@ use super::{
 const BAR_WIDTH: f32 = 150.;
 const BAR_HEIGHT: f32 = 15.;
 const BAR_BORDER_SIZE: f32 = 2.;
+const ABILITY_ICON_SIZE: f32 = 48.;
 
 pub(super) struct HudPlugin;
 
@@ -23,7 +27,12 @@ impl Plugin for HudPlugin {
             (
                 update_health_bar_display,
                 update_fire_bar_display,
+                update_fire_bar_overheat_display,
                 update_score_display,
+                update_breath_shape_display,
+                update_charge_bar_display,
+                update_ability_icons_display,
+                update_experience_display,
             )
                 .run_if(playing()),
         );
@@ -39,6 +48,24 @@ struct FireBreathBar;
 #[derive(Component)]
 struct ScoreDisplay;
 
+#[derive(Component)]
+struct BreathShapeDisplay;
+
+#[derive(Component)]
+struct ChargeBar;
+
+#[derive(Component)]
+struct ExperienceBar;
+
+#[derive(Component)]
+struct ExperienceLevelDisplay;
+
+#[derive(Component)]
+struct AbilityIcon(AbilityKind);
+
+#[derive(Component)]
+struct AbilityCooldownOverlay(AbilityKind);
+
 fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands
         .spawn((
@@ -83,29 +110,185 @@ fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ));
                 });
 
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(8.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|abilities_builder| {
+                    for ability_kind in AbilityKind::ALL {
+                        abilities_builder
+                            .spawn((
+                                AbilityIcon(ability_kind),
+                                NodeBundle {
+                                    background_color: BackgroundColor(Color::DARK_GRAY),
+                                    border_color: BorderColor(Color::BLACK),
+                                    style: Style {
+                                        border: UiRect::all(Val::Px(BAR_BORDER_SIZE)),
+                                        width: Val::Px(ABILITY_ICON_SIZE),
+                                        height: Val::Px(ABILITY_ICON_SIZE),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                            ))
+                            .with_children(|ability_icon_builder| {
+                                ability_icon_builder.spawn(TextBundle::from_section(
+                                    ability_kind.name(),
+                                    TextStyle {
+                                        font: asset_server
+                                            .get_handle("fonts/Prince Valiant.ttf")
+                                            .unwrap_or_default(),
+                                        font_size: 16.0,
+                                        color: Color::GOLD,
+                                    },
+                                ));
+
+                                ability_icon_builder.spawn((
+                                    AbilityCooldownOverlay(ability_kind),
+                                    NodeBundle {
+                                        background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+                                        style: Style {
+                                            position_type: PositionType::Absolute,
+                                            bottom: Val::Px(0.),
+                                            width: Val::Percent(100.),
+                                            height: Val::Percent(0.),
+                                            ..default()
+                                        },
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::End,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|fire_breath_builder| {
+                    fire_breath_builder.spawn((
+                        NodeBundle {
+                            background_color: BackgroundColor(Color::GOLD),
+                            style: Style {
+                                display: Display::None,
+                                width: Val::Px(0.),
+                                height: Val::Px(BAR_HEIGHT * 0.5),
+                                margin: UiRect::bottom(Val::Px(4.)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        ChargeBar,
+                    ));
+
+                    fire_breath_builder.spawn((
+                        BreathShapeDisplay,
+                        TextBundle::from_section(
+                            BreathShape::default().name(),
+                            TextStyle {
+                                font: asset_server
+                                    .get_handle("fonts/Prince Valiant.ttf")
+                                    .unwrap_or_default(),
+                                font_size: 20.0,
+                                color: Color::GOLD,
+                            },
+                        ),
+                    ));
+
+                    fire_breath_builder
+                        .spawn(NodeBundle {
+                            border_color: BorderColor(Color::BLACK),
+                            style: Style {
+                                border: UiRect::all(Val::Px(BAR_BORDER_SIZE)),
+                                width: Val::Px(BAR_WIDTH),
+                                height: Val::Px(BAR_HEIGHT),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|fire_breath_bar_builder| {
+                            fire_breath_bar_builder.spawn((
+                                NodeBundle {
+                                    background_color: BackgroundColor(Color::LIME_GREEN),
+                                    style: Style {
+                                        width: Val::Px(BAR_WIDTH - BAR_BORDER_SIZE * 2.),
+                                        height: Val::Px(BAR_HEIGHT - BAR_BORDER_SIZE * 2.),
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                FireBreathBar,
+                            ));
+                        });
+                });
+        });
+
+    // Experience bar along the top of the screen
+    commands
+        .spawn((
+            InGameEntity,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.),
+                    width: Val::Percent(100.),
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.),
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                ExperienceLevelDisplay,
+                TextBundle::from_section(
+                    "Lv 1",
+                    TextStyle {
+                        font: asset_server
+                            .get_handle("fonts/Prince Valiant.ttf")
+                            .unwrap_or_default(),
+                        font_size: 20.0,
+                        color: Color::GOLD,
+                    },
+                ),
+            ));
+
             builder
                 .spawn(NodeBundle {
                     border_color: BorderColor(Color::BLACK),
                     style: Style {
                         border: UiRect::all(Val::Px(BAR_BORDER_SIZE)),
-                        width: Val::Px(BAR_WIDTH),
-                        height: Val::Px(BAR_HEIGHT),
+                        flex_grow: 1.,
+                        height: Val::Px(BAR_HEIGHT * 0.5),
                         ..default()
                     },
                     ..default()
                 })
-                .with_children(|fire_breath_bar_builder| {
-                    fire_breath_bar_builder.spawn((
+                .with_children(|experience_bar_builder| {
+                    experience_bar_builder.spawn((
                         NodeBundle {
-                            background_color: BackgroundColor(Color::LIME_GREEN),
+                            background_color: BackgroundColor(Color::PURPLE),
                             style: Style {
-                                width: Val::Px(BAR_WIDTH - BAR_BORDER_SIZE * 2.),
-                                height: Val::Px(BAR_HEIGHT - BAR_BORDER_SIZE * 2.),
+                                width: Val::Percent(0.),
+                                height: Val::Percent(100.),
                                 ..default()
                             },
                             ..default()
                         },
-                        FireBreathBar,
+                        ExperienceBar,
                     ));
                 });
         });
@@ -162,6 +345,27 @@ fn update_fire_bar_display(
     }
 }
 
+fn update_fire_bar_overheat_display(
+    player_query: Query<Option<&Overheated>, With<Player>>,
+    mut fire_bar_query: Query<&mut BackgroundColor, With<FireBreathBar>>,
+) {
+    const FLASH_INTERVAL_SECONDS: f32 = 0.25;
+
+    if let Ok(overheated) = player_query.get_single() {
+        let mut background_color = fire_bar_query.single_mut();
+
+        background_color.0 = match overheated {
+            Some(overheated)
+                if (overheated.elapsed_secs() / FLASH_INTERVAL_SECONDS) as u32 % 2 == 0 =>
+            {
+                Color::ORANGE_RED
+            }
+            Some(_) => Color::DARK_GRAY,
+            None => Color::LIME_GREEN,
+        };
+    }
+}
+
 fn update_score_display(
     player_query: Query<&Score, (Changed<Score>, With<Player>)>,
     mut score_text_display_query: Query<&mut Text, With<ScoreDisplay>>,
@@ -175,3 +379,68 @@ fn update_score_display(
         );
     }
 }
+
+fn update_breath_shape_display(
+    player_query: Query<&BreathShape, (Changed<BreathShape>, With<Player>)>,
+    mut breath_shape_text_display_query: Query<&mut Text, With<BreathShapeDisplay>>,
+) {
+    if let Ok(breath_shape) = player_query.get_single() {
+        let mut breath_shape_text = breath_shape_text_display_query.single_mut();
+        breath_shape_text.sections[0].value = breath_shape.name().to_string();
+    }
+}
+
+fn update_charge_bar_display(
+    player_query: Query<&BreathCharge, (Changed<BreathCharge>, With<Player>)>,
+    mut charge_bar_query: Query<&mut Style, With<ChargeBar>>,
+) {
+    if let Ok(breath_charge) = player_query.get_single() {
+        let mut style = charge_bar_query.single_mut();
+
+        style.display = if breath_charge.0 > 0. {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        style.width = Val::Px(BAR_WIDTH * breath_charge.0);
+    }
+}
+
+fn update_ability_icons_display(
+    player_query: Query<&Abilities, With<Player>>,
+    mut ability_icon_query: Query<(&AbilityIcon, &mut Style), Without<AbilityCooldownOverlay>>,
+    mut ability_cooldown_overlay_query: Query<(&AbilityCooldownOverlay, &mut Style)>,
+) {
+    let Ok(abilities) = player_query.get_single() else {
+        return;
+    };
+
+    for (AbilityIcon(ability_kind), mut style) in &mut ability_icon_query {
+        style.display = if abilities.is_unlocked(*ability_kind) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for (AbilityCooldownOverlay(ability_kind), mut style) in &mut ability_cooldown_overlay_query {
+        style.height = Val::Percent(abilities.cooldown_remaining(*ability_kind) * 100.);
+    }
+}
+
+fn update_experience_display(
+    player_query: Query<
+        (&ResourcePool<Experience>, &ExperienceLevel),
+        (Changed<ResourcePool<Experience>>, With<Player>),
+    >,
+    mut experience_bar_query: Query<&mut Style, With<ExperienceBar>>,
+    mut experience_level_text_display_query: Query<&mut Text, With<ExperienceLevelDisplay>>,
+) {
+    if let Ok((experience, experience_level)) = player_query.get_single() {
+        let mut style = experience_bar_query.single_mut();
+        let mut experience_level_text = experience_level_text_display_query.single_mut();
+
+        style.width = Val::Percent(experience.current_percentage() * 100.);
+        experience_level_text.sections[0].value = format!("Lv {}", **experience_level);
+    }
+}
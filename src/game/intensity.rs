@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::{audio::MusicIntensity, playing, AppState};
+
+use super::{
+    enemy::{EnemyKind, EnemySpawnCounter},
+    resource_pool::{Health, ResourcePool},
+    Enemy, Player, TILE_SIZE,
+};
+
+pub(super) struct IntensityPlugin;
+
+impl Plugin for IntensityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, update_music_intensity.run_if(playing()));
+
+        app.add_systems(OnExit(AppState::InGame), reset_music_intensity);
+    }
+}
+
+/// Rates the fight from the enemies closing in, how far the waves have come and how hurt the
+/// dragon is. Only a swarm on a wounded dragon late in the run reaches the climax layer.
+fn update_music_intensity(
+    mut music_intensity: ResMut<MusicIntensity>,
+    player_query: Query<(&Transform, &ResourcePool<Health>), With<Player>>,
+    enemy_query: Query<(&Transform, &EnemyKind), With<Enemy>>,
+    enemy_spawn_counter: Option<Res<EnemySpawnCounter>>,
+) {
+    const NEARBY_TILES: f32 = 12.;
+    const SWARM_SIZE: f32 = 12.;
+    const LAST_WAVE: f32 = 6.;
+    const NEARBY_WEIGHT: f32 = 0.5;
+    const WAVE_WEIGHT: f32 = 0.25;
+    const DANGER_WEIGHT: f32 = 0.25;
+
+    let Ok((player_transform, health)) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    let nearby_enemies = enemy_query
+        .iter()
+        .filter(|(enemy_transform, &kind)| {
+            kind != EnemyKind::Building
+                && enemy_transform
+                    .translation
+                    .truncate()
+                    .distance(player_position)
+                    <= TILE_SIZE.x * NEARBY_TILES
+        })
+        .count();
+    let wave = enemy_spawn_counter.map_or(0, |enemy_spawn_counter| enemy_spawn_counter.wave());
+
+    let nearby = (nearby_enemies as f32 / SWARM_SIZE).min(1.);
+    let progress = (wave as f32 / LAST_WAVE).min(1.);
+    let danger = 1. - health.current_percentage();
+
+    music_intensity.0 =
+        (nearby * NEARBY_WEIGHT + progress * WAVE_WEIGHT + danger * DANGER_WEIGHT).clamp(0., 1.);
+}
+
+fn reset_music_intensity(mut music_intensity: ResMut<MusicIntensity>) {
+    music_intensity.0 = 0.;
+}
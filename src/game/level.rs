@@ -5,7 +5,10 @@ use pathfinding::prelude::Matrix;
 use rand::{random, seq::SliceRandom, Rng};
 
 use crate::{
-    audio::{PlayMusicEvent, PlaybackSettings, SoundEffect},
+    audio::{
+        AudioBus, AudioBusChannels, MusicLayer, PlayMusicEvent, PlaySoundEffectEvent,
+        PlaybackSettings, SoundEffect,
+    },
     camera::{RenderLayer, YSorted, YSortedInverse},
     entity_cleanup,
     game::{
@@ -17,6 +20,8 @@ use crate::{
 
 use super::{
     combat::{AttackDamage, AttackTimer, Range},
+    enemy::EnemyKind,
+    projectile::ProjectileKind,
     resource_pool::{Health, ResourcePool},
     Enemy,
 };
@@ -25,30 +30,24 @@ pub(super) struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnTransition {
-                from: AppState::MainMenu,
-                to: AppState::InGame,
-            },
-            (generate_level_matrix, generate_tilemaps),
-        );
-
         app.add_systems(
             OnEnter(AppState::InGame),
             (
+                (generate_level_matrix, generate_tilemaps),
                 spawn_level_tiles,
                 spawn_buildings,
                 spawn_hills,
                 spawn_mountains,
                 spawn_waves,
                 play_background_music,
+                play_ambience,
             )
                 .chain(),
         );
 
         app.add_systems(
             OnExit(AppState::InGame),
-            entity_cleanup::<With<SoundEffect>>,
+            (entity_cleanup::<With<SoundEffect>>, stop_ambience),
         );
     }
 }
@@ -166,7 +165,13 @@ fn spawn_buildings(
             collider: Collider::ball(HALF_TILE_SIZE.x),
             collision_groups: CollisionGroups::new(BUILDING_GROUP, ENEMY_GROUP | FIRE_BREATH_GROUP),
             hitpoints: ResourcePool::<Health>::new(1000),
+            kind: EnemyKind::Building,
             marker: Enemy,
+            projectile_kind: if rng.gen_bool(0.5) {
+                ProjectileKind::Bolt
+            } else {
+                ProjectileKind::Rock
+            },
             range: Range(TILE_SIZE.x * 20.),
             render_layers: RenderLayers::layer(RenderLayer::Ground.into()),
             rigid_body: RigidBody::Fixed,
@@ -341,15 +346,36 @@ fn spawn_waves(
 }
 
 fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
-    play_music_event_writer.send(PlayMusicEvent::new(
-        "theme2.ogg",
-        Some(PlaybackSettings {
-            loop_from: Some(0.0),
-            volume: 0.25,
-            ..default()
-        }),
-        None,
-    ));
+    play_music_event_writer.send(
+        PlayMusicEvent::new(
+            "theme2.ogg",
+            Some(PlaybackSettings {
+                loop_from: Some(0.0),
+                ..default()
+            }),
+            None,
+        )
+        .with_layers(MusicLayer::ALL),
+    );
+}
+
+/// Loops the wind over the fields on the ambience bus for as long as the run lasts.
+fn play_ambience(mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>) {
+    play_sound_effect_event_writer.send(
+        PlaySoundEffectEvent::new(
+            "ambience.ogg",
+            Some(PlaybackSettings {
+                loop_from: Some(0.0),
+                ..default()
+            }),
+            None,
+        )
+        .with_bus(AudioBus::Ambience),
+    );
+}
+
+fn stop_ambience(audio_bus_channels: AudioBusChannels) {
+    audio_bus_channels.stop_ambience();
 }
 
 #[derive(Resource, Deref)]
@@ -369,7 +395,9 @@ pub struct BuildingBundle {
     pub collider: Collider,
     pub collision_groups: CollisionGroups,
     pub hitpoints: ResourcePool<Health>,
+    pub kind: EnemyKind,
     pub marker: Enemy,
+    pub projectile_kind: ProjectileKind,
     pub range: Range,
     pub sprite: SpriteBundle,
     pub render_layers: RenderLayers,
@@ -1,22 +1,34 @@
+mod ability;
 mod combat;
 mod constants;
 mod enemy;
+mod experience;
 mod fire_breath;
+mod floating_text;
 mod game_over;
 mod hud;
+mod intensity;
 mod level;
+mod pause;
 mod player;
 mod plugin;
 mod power_up;
+mod projectile;
 mod resource_pool;
 mod score_system;
+mod statistics;
 
 use plugin::InGameEntity;
 
+pub use ability::{AbilityKind, Dashing, UseAbilityEvent};
+pub use combat::{Netted, PlayerHitEvent, Slowed};
 pub use constants::*;
 pub use enemy::Enemy;
-pub use fire_breath::SpawnFireBreathEvent;
+pub use fire_breath::{
+    BreathCharge, BreathElement, BreathShape, ChargedBreathEvent, Overheated, SpawnFireBreathEvent,
+    UnlockedBreathShapes,
+};
 pub use level::{BorderTile, Tile};
-pub use player::Player;
+pub use player::{Altitude, Banking, Player};
 pub use plugin::GamePlugin;
 pub use resource_pool::*;
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use bevy_rapier2d::plugin::RapierConfiguration;
+
+use crate::{
+    audio::{AudioBusChannels, BreathAudio},
+    entity_cleanup,
+    input::{Action, ActionState, ActionSystem},
+    settings::SettingsMenuState,
+    AppState, InGameState,
+};
+
+use super::{BreathCharge, InGameEntity, Player};
+
+pub(super) struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                toggle_pause
+                    .after(ActionSystem)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(SettingsMenuState::Closed)),
+                handle_pause_button_interactions.run_if(in_state(InGameState::Paused)),
+            ),
+        );
+
+        app.add_systems(
+            OnEnter(InGameState::Paused),
+            (
+                pause_physics,
+                pause_time,
+                pause_audio,
+                cancel_breath_charge,
+                display_pause_menu,
+            ),
+        );
+
+        app.add_systems(
+            OnExit(InGameState::Paused),
+            (
+                resume_physics,
+                resume_time,
+                resume_audio,
+                entity_cleanup::<With<PauseEntity>>,
+            ),
+        );
+
+        // Leaving a run from the pause menu skips the game over screen, which would otherwise
+        // clean up after it.
+        app.add_systems(
+            OnTransition {
+                from: AppState::InGame,
+                to: AppState::MainMenu,
+            },
+            entity_cleanup::<With<InGameEntity>>,
+        );
+        app.add_systems(
+            OnTransition {
+                from: AppState::InGame,
+                to: AppState::Restarting,
+            },
+            entity_cleanup::<With<InGameEntity>>,
+        );
+
+        app.add_systems(OnEnter(AppState::Restarting), restart_game);
+    }
+}
+
+#[derive(Component)]
+struct PauseEntity;
+
+#[derive(Component)]
+enum PauseButtonAction {
+    Resume,
+    Settings,
+    Restart,
+    QuitToMenu,
+}
+
+fn toggle_pause(
+    action_state: Res<ActionState>,
+    in_game_state: Res<State<InGameState>>,
+    mut next_state: ResMut<NextState<InGameState>>,
+) {
+    if !action_state.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match in_game_state.get() {
+        InGameState::Running => next_state.set(InGameState::Paused),
+        InGameState::Paused => next_state.set(InGameState::Running),
+        InGameState::LevelUp => {}
+    }
+}
+
+pub(super) fn pause_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = false;
+}
+
+pub(super) fn resume_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = true;
+}
+
+/// Stops virtual time, which holds back `FixedUpdate` and every timer and particle ticked by it.
+pub(super) fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub(super) fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+pub(super) fn pause_audio(audio_bus_channels: AudioBusChannels, breath_audio: BreathAudio) {
+    audio_bus_channels.pause_game();
+    // Input is cleared on pause, so the held breath and charge would never be released to end
+    // their loops.
+    breath_audio.stop();
+    breath_audio.stop_charge();
+}
+
+/// Drops a charge in progress, since the release that would fire it is lost with the input.
+pub(super) fn cancel_breath_charge(mut query: Query<&mut BreathCharge, With<Player>>) {
+    for mut breath_charge in &mut query {
+        breath_charge.0 = 0.;
+    }
+}
+
+pub(super) fn resume_audio(audio_bus_channels: AudioBusChannels) {
+    audio_bus_channels.resume_game();
+}
+
+fn display_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+
+    commands
+        .spawn((
+            PauseEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+                style: Style {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    row_gap: Val::Px(16.),
+                    width: Val::Percent(100.),
+                    ..default()
+                },
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    color: Color::WHITE,
+                    font: font.clone(),
+                    font_size: 64.,
+                },
+            ));
+
+            for (label, action) in [
+                ("Resume", PauseButtonAction::Resume),
+                ("Settings", PauseButtonAction::Settings),
+                ("Restart", PauseButtonAction::Restart),
+                ("Quit to Menu", PauseButtonAction::QuitToMenu),
+            ] {
+                builder
+                    .spawn((
+                        ButtonBundle {
+                            background_color: Color::ALICE_BLUE.into(),
+                            style: Style {
+                                justify_content: JustifyContent::Center,
+                                padding: UiRect::all(Val::Px(8.)),
+                                width: Val::Px(320.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        action,
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                color: Color::BLACK,
+                                font: font.clone(),
+                                font_size: 32.,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_pause_button_interactions(
+    mut app_state: ResMut<NextState<AppState>>,
+    mut in_game_state: ResMut<NextState<InGameState>>,
+    mut settings_menu_state: ResMut<NextState<SettingsMenuState>>,
+    query: Query<(&Interaction, &PauseButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, pause_button_action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match pause_button_action {
+            PauseButtonAction::Resume => {}
+            PauseButtonAction::Settings => {
+                settings_menu_state.set(SettingsMenuState::Open);
+                continue;
+            }
+            PauseButtonAction::Restart => app_state.set(AppState::Restarting),
+            PauseButtonAction::QuitToMenu => app_state.set(AppState::MainMenu),
+        }
+
+        in_game_state.set(InGameState::Running);
+        break;
+    }
+}
+
+/// A state can't transition to itself, so restarting passes through
+/// [`AppState::Restarting`] to leave the run and start a fresh one with the same species.
+fn restart_game(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::InGame);
+}
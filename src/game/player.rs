@@ -4,14 +4,20 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     animation::{AnimationIndices, AnimationTimer},
     camera::{RenderLayer, YSorted},
+    lair::{Hoard, Unlock},
     physics::Speed,
+    playing,
+    species::{DragonSpecies, SelectedSpecies},
     AppState,
 };
 
 use super::{
-    resource_pool::{Fire, Health, ResourcePool},
+    ability::Abilities,
+    experience::ExperienceLevel,
+    fire_breath::{BreathCharge, BreathShape, FireRegeneration, UnlockedBreathShapes},
+    resource_pool::{Experience, Fire, Health, ResourcePool},
     score_system::Score,
-    InGameEntity, PLAYER_GROUP, POWERUP_GROUP, PROJECTILE_GROUP,
+    InGameEntity, ANTI_AIR_GROUP, PLAYER_GROUP, POWERUP_GROUP, PROJECTILE_GROUP,
 };
 
 pub(super) struct PlayerPlugin;
@@ -19,56 +25,307 @@ pub(super) struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::InGame), spawn_player);
+
+        app.add_systems(
+            Update,
+            (
+                update_altitude_collision_groups,
+                update_dragon_sprite,
+                update_dragon_shadow,
+            )
+                .run_if(playing()),
+        );
     }
 }
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
+    pub abilities: Abilities,
+    pub altitude: Altitude,
     pub animation_indices: AnimationIndices,
     pub animation_timer: AnimationTimer,
+    pub banking: Banking,
+    pub breath_charge: BreathCharge,
+    pub breath_shape: BreathShape,
     pub collider: Collider,
     pub collision_groups: CollisionGroups,
+    pub experience: ResourcePool<Experience>,
+    pub experience_level: ExperienceLevel,
     pub fire_breath_resource: ResourcePool<Fire>,
+    pub fire_regeneration: FireRegeneration,
     pub hitpoints: ResourcePool<Health>,
     pub score: Score,
     pub speed: Speed,
     pub marker: Player,
     pub render_layers: RenderLayers,
+    pub rigid_body: RigidBody,
     pub spritesheet: SpriteSheetBundle,
+    pub unlocked_breath_shapes: UnlockedBreathShapes,
+    pub velocity: Velocity,
 }
 
 #[derive(Component)]
 pub struct Player;
 
-fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// How high the dragon is flying.
+///
+/// Flying high keeps the dragon out of reach of everything but anti-air projectiles at the
+/// cost of a weaker breath. Flying low exposes it to every attack but lets it breathe at full
+/// strength and claw buildings.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Altitude {
+    #[default]
+    High,
+    Low,
+}
+
+impl Altitude {
+    /// How strong the breath is relative to its full strength. Damage can't drop below 1, so
+    /// a weaker breath also leaves its damage area behind for less time.
+    pub fn breath_strength(&self) -> f32 {
+        match self {
+            Self::High => 0.5,
+            Self::Low => 1.,
+        }
+    }
+
+    /// Scales a breath's `full_damage` down to its strength at this altitude.
+    pub fn breath_damage(&self, full_damage: i16) -> i16 {
+        ((full_damage as f32 * self.breath_strength()).round() as i16).max(1)
+    }
+
+    pub fn collision_groups(&self) -> CollisionGroups {
+        match self {
+            Self::High => CollisionGroups::new(PLAYER_GROUP, ANTI_AIR_GROUP | POWERUP_GROUP),
+            Self::Low => CollisionGroups::new(PLAYER_GROUP, PROJECTILE_GROUP | POWERUP_GROUP),
+        }
+    }
+
+    /// Size of the dragon sprite relative to its frame size.
+    fn sprite_scale(&self) -> f32 {
+        match self {
+            Self::High => 1.,
+            Self::Low => 0.8,
+        }
+    }
+
+    /// Where the shadow is drawn relative to the dragon.
+    fn shadow_offset(&self) -> Vec2 {
+        match self {
+            Self::High => Vec2::new(40., -80.),
+            Self::Low => Vec2::new(12., -24.),
+        }
+    }
+}
+
+#[derive(Component)]
+struct DragonShadow;
+
+/// How the species' sprite sheet is laid out and how big the dragon is drawn.
+#[derive(Component)]
+struct DragonSprite {
+    frame_size: Vec2,
+    flight_frames: (usize, usize),
+    banking_frame: usize,
+    scale: f32,
+    /// Scale the sprite is drawn at right now, easing towards its altitude's scale.
+    current_scale: f32,
+}
+
+/// How hard the dragon is leaning into a turn, from -1 (right) to 1 (left).
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct Banking(pub f32);
+
+fn spawn_player(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    hoard: Res<Hoard>,
+    selected_species: Res<SelectedSpecies>,
+    species_assets: Res<Assets<DragonSpecies>>,
+) {
+    const TOUGH_SCALES_HEALTH: i16 = 25;
+    const DEEP_LUNGS_FIRE: i16 = 25;
+    const SWIFT_WINGS_SPEED: f32 = 1.1;
+
+    let Some(species) = species_assets.get(selected_species.0) else {
+        error!("The selected dragon species isn't loaded.");
+        return;
+    };
     let texture = asset_server
-        .get_handle("textures/dragon.png")
+        .get_handle(&species.texture)
         .unwrap_or_default();
     let texture_atlas_layout =
-        TextureAtlasLayout::from_grid(Vec2::new(191., 161.), 12, 1, None, None);
+        TextureAtlasLayout::from_grid(species.frame_size, species.columns, 1, None, None);
     let texture_atlas_layout_handle = asset_server.add(texture_atlas_layout);
 
+    let altitude = Altitude::default();
+    let mut max_health = species.health;
+    let mut max_fire = species.fire;
+    let mut speed = species.speed;
+
+    if hoard.has(Unlock::ToughScales) {
+        max_health += TOUGH_SCALES_HEALTH;
+    }
+
+    if hoard.has(Unlock::DeepLungs) {
+        max_fire += DEEP_LUNGS_FIRE;
+    }
+
+    if hoard.has(Unlock::SwiftWings) {
+        speed *= SWIFT_WINGS_SPEED;
+    }
+
+    let breath_shapes = [
+        Some(BreathShape::Cone),
+        hoard.has(Unlock::BeamBreath).then_some(BreathShape::Beam),
+        hoard
+            .has(Unlock::FireballBreath)
+            .then_some(BreathShape::Fireball),
+    ];
+
+    commands.spawn((
+        DragonShadow,
+        InGameEntity,
+        RenderLayers::layer(RenderLayer::Ground.into()),
+        SpriteSheetBundle {
+            atlas: TextureAtlas {
+                layout: texture_atlas_layout_handle.clone(),
+                index: 0,
+            },
+            sprite: Sprite {
+                color: Color::BLACK.with_a(0.3),
+                ..default()
+            },
+            texture: texture.clone(),
+            ..default()
+        },
+    ));
+
     let mut player_entity_commands = commands.spawn(PlayerBundle {
-        animation_indices: AnimationIndices::new(0, 2),
+        abilities: Abilities::default(),
+        altitude,
+        animation_indices: AnimationIndices::new(species.flight_frames.0, species.flight_frames.1),
         animation_timer: AnimationTimer::from_seconds(0.2),
-        collider: Collider::cuboid(15., 40.),
-        collision_groups: CollisionGroups::new(PLAYER_GROUP, PROJECTILE_GROUP | POWERUP_GROUP),
-        fire_breath_resource: ResourcePool::<Fire>::new(100),
-        hitpoints: ResourcePool::<Health>::new(100),
+        banking: Banking::default(),
+        breath_charge: BreathCharge::default(),
+        breath_shape: BreathShape::default(),
+        collider: Collider::cuboid(species.hitbox.x, species.hitbox.y),
+        collision_groups: altitude.collision_groups(),
+        experience: ResourcePool::<Experience>::new_with_current(50, 0),
+        experience_level: ExperienceLevel::default(),
+        fire_breath_resource: ResourcePool::<Fire>::new(max_fire),
+        fire_regeneration: FireRegeneration::default(),
+        hitpoints: ResourcePool::<Health>::new(max_health),
         score: Score::new(0, 1),
         marker: Player,
         render_layers: RenderLayers::layer(RenderLayer::Sky.into()),
-        speed: Speed(10.),
+        rigid_body: RigidBody::KinematicVelocityBased,
+        speed: Speed(speed),
         spritesheet: SpriteSheetBundle {
             atlas: TextureAtlas {
                 layout: texture_atlas_layout_handle,
                 index: 0,
             },
+            sprite: Sprite {
+                color: hoard.skin.color(),
+                ..default()
+            },
             texture,
             transform: Transform::from_translation(Vec2::ONE.extend(1.)),
             ..default()
         },
+        unlocked_breath_shapes: UnlockedBreathShapes::new(breath_shapes.into_iter().flatten()),
+        velocity: Velocity::zero(),
     });
 
-    player_entity_commands.insert((InGameEntity, YSorted));
+    player_entity_commands.insert((
+        species.breath,
+        DragonSprite {
+            frame_size: species.frame_size,
+            flight_frames: species.flight_frames,
+            banking_frame: species.banking_frame,
+            scale: species.scale,
+            current_scale: altitude.sprite_scale() * species.scale,
+        },
+        hoard.skin,
+        InGameEntity,
+        YSorted,
+    ));
+}
+
+fn update_altitude_collision_groups(
+    mut query: Query<(&Altitude, &mut CollisionGroups), (Changed<Altitude>, With<Player>)>,
+) {
+    for (altitude, mut collision_groups) in &mut query {
+        *collision_groups = altitude.collision_groups();
+    }
+}
+
+/// Scales the dragon towards its altitude's size. In a turn the dragon holds its species'
+/// banking frame, mirrored for left turns, and narrows its wingspan.
+fn update_dragon_sprite(
+    mut query: Query<
+        (
+            &Altitude,
+            &Banking,
+            &mut DragonSprite,
+            &mut AnimationIndices,
+            &mut Sprite,
+        ),
+        With<Player>,
+    >,
+    time: Res<Time>,
+) {
+    const HARD_BANKING: f32 = 0.5;
+    const MAX_WINGSPAN_REDUCTION: f32 = 0.3;
+    const ALTITUDE_CHANGE_RATE: f32 = 4.;
+
+    let Ok((altitude, banking, mut dragon_sprite, mut animation_indices, mut sprite)) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+    let target_scale = altitude.sprite_scale() * dragon_sprite.scale;
+
+    dragon_sprite.current_scale += (target_scale - dragon_sprite.current_scale)
+        * (ALTITUDE_CHANGE_RATE * time.delta_seconds()).min(1.);
+    *animation_indices = if banking.abs() > HARD_BANKING {
+        AnimationIndices::new(dragon_sprite.banking_frame, dragon_sprite.banking_frame)
+    } else {
+        AnimationIndices::new(dragon_sprite.flight_frames.0, dragon_sprite.flight_frames.1)
+    };
+    sprite.flip_x = **banking > HARD_BANKING;
+    sprite.custom_size = Some(
+        Vec2::new(
+            dragon_sprite.frame_size.x * (1. - MAX_WINGSPAN_REDUCTION * banking.abs()),
+            dragon_sprite.frame_size.y,
+        ) * dragon_sprite.current_scale,
+    );
+}
+
+fn update_dragon_shadow(
+    player_query: Query<(&Altitude, &Transform, &TextureAtlas, &Sprite), With<Player>>,
+    mut shadow_query: Query<
+        (&mut Transform, &mut TextureAtlas, &mut Sprite),
+        (With<DragonShadow>, Without<Player>),
+    >,
+) {
+    const SHADOW_SCALE: f32 = 0.7;
+
+    let Ok((altitude, player_transform, player_atlas, player_sprite)) = player_query.get_single()
+    else {
+        return;
+    };
+    let Ok((mut shadow_transform, mut shadow_atlas, mut shadow_sprite)) =
+        shadow_query.get_single_mut()
+    else {
+        return;
+    };
+
+    shadow_transform.translation =
+        (player_transform.translation.truncate() + altitude.shadow_offset()).extend(0.);
+    shadow_transform.rotation = player_transform.rotation;
+    shadow_atlas.index = player_atlas.index;
+    shadow_sprite.flip_x = player_sprite.flip_x;
+    shadow_sprite.custom_size = player_sprite.custom_size.map(|size| size * SHADOW_SCALE);
 }
@@ -1,9 +1,11 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 use super::{
-    combat::CombatPlugin, enemy::EnemyPlugin, fire_breath::FireBreathPlugin,
-    game_over::GameOverPlugin, hud::HudPlugin, level::LevelPlugin, player::PlayerPlugin,
-    power_up::PowerUpSystemPlugin, score_system::ScoreSystemPlugin,
+    ability::AbilityPlugin, combat::CombatPlugin, enemy::EnemyPlugin, experience::ExperiencePlugin,
+    fire_breath::FireBreathPlugin, floating_text::FloatingTextPlugin, game_over::GameOverPlugin,
+    hud::HudPlugin, intensity::IntensityPlugin, level::LevelPlugin, pause::PausePlugin,
+    player::PlayerPlugin, power_up::PowerUpSystemPlugin, score_system::ScoreSystemPlugin,
+    statistics::StatisticsPlugin,
 };
 
 pub struct GamePlugin;
@@ -11,15 +13,21 @@ pub struct GamePlugin;
 impl PluginGroup for GamePlugin {
     fn build(self) -> bevy::app::PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(AbilityPlugin)
             .add(CombatPlugin)
             .add(EnemyPlugin)
+            .add(ExperiencePlugin)
             .add(FireBreathPlugin)
+            .add(FloatingTextPlugin)
             .add(GameOverPlugin)
             .add(HudPlugin)
+            .add(IntensityPlugin)
             .add(LevelPlugin)
+            .add(PausePlugin)
             .add(PlayerPlugin)
             .add(PowerUpSystemPlugin)
             .add(ScoreSystemPlugin)
+            .add(StatisticsPlugin)
     }
 }
 
@@ -23,6 +23,7 @@ pub(super) struct PowerUpSystemPlugin;
 impl Plugin for PowerUpSystemPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PowerUpEvent>();
+        app.add_event::<PowerUpCollectedEvent>();
         app.add_systems(OnEnter(AppState::InGame), load_scale_atlas);
         app.add_systems(
             FixedUpdate,
@@ -51,6 +52,9 @@ impl PowerUpEvent {
     }
 }
 
+#[derive(Event)]
+pub struct PowerUpCollectedEvent;
+
 #[derive(Bundle)]
 pub struct PowerUpBundle {
     pub marker: PowerUp,
@@ -133,6 +137,7 @@ fn spawn_powerups(
 
 fn consume_powerups(
     mut commands: Commands,
+    mut powerup_collected_event_writer: EventWriter<PowerUpCollectedEvent>,
     powerup_query: Query<Entity, With<PowerUp>>,
     mut player_query: Query<&mut ResourcePool<Health>, With<Player>>,
     rapier_context: Res<RapierContext>,
@@ -143,6 +148,7 @@ fn consume_powerups(
                 if let Ok(mut hitpoints) = player_query.get_single_mut() {
                     hitpoints.add(50);
                     commands.entity(entity).despawn_recursive();
+                    powerup_collected_event_writer.send(PowerUpCollectedEvent);
                 }
             }
         }
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Collider;
+use std::collections::HashMap;
+
+use super::TILE_SIZE;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProjectileKind {
+    Arrow,
+    FireArrow,
+    Net,
+    Bolt,
+    Rock,
+}
+
+/// Determines whether fire breath can burn a projectile mid-flight.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectileMaterial {
+    Wood,
+    Stone,
+    Metal,
+}
+
+impl ProjectileMaterial {
+    pub fn is_flammable(&self) -> bool {
+        *self == Self::Wood
+    }
+}
+
+/// What happens to the target when a projectile hits it.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum ProjectileEffect {
+    Damage,
+    Slow { factor: f32, seconds: f32 },
+    Net { seconds: f32 },
+    Splash { radius: f32 },
+}
+
+#[derive(Clone)]
+pub struct ProjectileDefinition {
+    /// Whether the projectile can reach the dragon while it's flying high.
+    pub anti_air: bool,
+    pub arc_height: f32,
+    /// Frame of the projectile in [`ProjectileAtlas`].
+    pub atlas_index: usize,
+    pub collider: Collider,
+    pub damping: f32,
+    pub effect: ProjectileEffect,
+    /// Sound effect played where the projectile is launched.
+    pub launch_sound: &'static str,
+    pub lifetime: f32,
+    pub material: ProjectileMaterial,
+    /// Size the frame is drawn at, which can be larger than the collider.
+    pub size: Vec2,
+    pub speed_factor: f32,
+}
+
+/// The atlas layout of `projectiles.png`, which holds a frame for every kind of projectile.
+#[derive(Resource, Deref)]
+pub struct ProjectileAtlas(Handle<TextureAtlasLayout>);
+
+impl FromWorld for ProjectileAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let texture_atlas_layout =
+            TextureAtlasLayout::from_grid(Vec2::new(16., 16.), 5, 1, None, None);
+
+        Self(
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(texture_atlas_layout),
+        )
+    }
+}
+
+#[derive(Resource, Deref)]
+pub struct ProjectileRegistry(HashMap<ProjectileKind, ProjectileDefinition>);
+
+impl Default for ProjectileRegistry {
+    fn default() -> Self {
+        let arrow_size = Vec2::new(TILE_SIZE.x, 4.);
+        let net_size = Vec2::splat(TILE_SIZE.x);
+        let bolt_size = Vec2::new(TILE_SIZE.x * 0.75, 6.);
+        let rock_size = Vec2::splat(TILE_SIZE.x * 0.75);
+
+        Self(HashMap::from([
+            (
+                ProjectileKind::Arrow,
+                ProjectileDefinition {
+                    anti_air: false,
+                    arc_height: 0.25,
+                    atlas_index: 0,
+                    collider: Collider::cuboid(arrow_size.x / 2., arrow_size.y / 2.),
+                    damping: 1.0,
+                    effect: ProjectileEffect::Damage,
+                    launch_sound: "volley.ogg",
+                    lifetime: 2.0,
+                    material: ProjectileMaterial::Wood,
+                    size: TILE_SIZE,
+                    speed_factor: 1.0,
+                },
+            ),
+            (
+                ProjectileKind::FireArrow,
+                ProjectileDefinition {
+                    anti_air: false,
+                    arc_height: 0.25,
+                    atlas_index: 1,
+                    collider: Collider::cuboid(arrow_size.x / 2., arrow_size.y / 2.),
+                    damping: 1.0,
+                    effect: ProjectileEffect::Splash {
+                        radius: TILE_SIZE.x * 3.,
+                    },
+                    launch_sound: "volley.ogg",
+                    lifetime: 2.0,
+                    material: ProjectileMaterial::Wood,
+                    size: TILE_SIZE,
+                    speed_factor: 0.9,
+                },
+            ),
+            (
+                ProjectileKind::Net,
+                ProjectileDefinition {
+                    anti_air: false,
+                    arc_height: 0.75,
+                    atlas_index: 2,
+                    collider: Collider::ball(net_size.x / 2.),
+                    damping: 1.5,
+                    effect: ProjectileEffect::Net { seconds: 1.5 },
+                    launch_sound: "throw.ogg",
+                    lifetime: 1.0,
+                    material: ProjectileMaterial::Wood,
+                    size: net_size,
+                    speed_factor: 0.6,
+                },
+            ),
+            (
+                ProjectileKind::Bolt,
+                ProjectileDefinition {
+                    anti_air: true,
+                    arc_height: 0.,
+                    atlas_index: 3,
+                    collider: Collider::cuboid(bolt_size.x / 2., bolt_size.y / 2.),
+                    damping: 0.5,
+                    effect: ProjectileEffect::Slow {
+                        factor: 0.5,
+                        seconds: 2.0,
+                    },
+                    launch_sound: "bolt.ogg",
+                    lifetime: 3.0,
+                    material: ProjectileMaterial::Metal,
+                    size: TILE_SIZE,
+                    speed_factor: 1.25,
+                },
+            ),
+            (
+                ProjectileKind::Rock,
+                ProjectileDefinition {
+                    anti_air: false,
+                    arc_height: 1.0,
+                    atlas_index: 4,
+                    collider: Collider::ball(rock_size.x / 2.),
+                    damping: 1.0,
+                    effect: ProjectileEffect::Damage,
+                    launch_sound: "throw.ogg",
+                    lifetime: 1.5,
+                    material: ProjectileMaterial::Stone,
+                    size: rock_size,
+                    speed_factor: 0.75,
+                },
+            ),
+        ]))
+    }
+}
@@ -92,3 +92,6 @@ pub struct Fire;
 
 #[derive(Component)]
 pub struct Health;
+
+#[derive(Component)]
+pub struct Experience;
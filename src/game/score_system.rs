@@ -9,7 +9,7 @@ use bevy::{
 
 use crate::playing;
 
-use super::Player;
+use super::{floating_text::SpawnFloatingTextEvent, Player};
 
 pub(super) struct ScoreSystemPlugin;
 
@@ -67,6 +67,7 @@ impl Score {
 #[derive(Event)]
 pub struct ScoreEvent {
     points: i32,
+    position: Option<Vec2>,
     score_event_type: ScoreEventType,
 }
 #[derive(Component)]
@@ -76,9 +77,10 @@ pub enum ScoreEventType {
 }
 
 impl ScoreEvent {
-    pub fn new(points: i32, score_event_type: ScoreEventType) -> Self {
+    pub fn new(points: i32, score_event_type: ScoreEventType, position: Option<Vec2>) -> Self {
         Self {
             points,
+            position,
             score_event_type,
         }
     }
@@ -86,23 +88,38 @@ impl ScoreEvent {
 
 fn update_player_score(
     mut score_event_reader: EventReader<ScoreEvent>,
-    mut player_score_query: Query<&mut Score, With<Player>>,
+    mut spawn_floating_text_event_writer: EventWriter<SpawnFloatingTextEvent>,
+    mut player_score_query: Query<(&mut Score, &Transform), With<Player>>,
 ) {
     for ScoreEvent {
         points,
+        position,
         score_event_type,
     } in score_event_reader.read()
     {
-        let Ok(mut score_system) = player_score_query.get_single_mut() else {
+        let Ok((mut score_system, player_transform)) = player_score_query.get_single_mut() else {
             return;
         };
+        let position = position.unwrap_or(player_transform.translation.truncate());
 
         match score_event_type {
             ScoreEventType::AddPoints => {
+                spawn_floating_text_event_writer.send(SpawnFloatingTextEvent::new(
+                    format!("+{points} x {}", score_system.multiplier()),
+                    position,
+                    Color::GOLD,
+                ));
                 score_system.add_with_multiplier(*points);
                 score_system.increase_multiplier_by_one();
             }
             ScoreEventType::ResetMultiplier => {
+                if score_system.multiplier() > 1 {
+                    spawn_floating_text_event_writer.send(SpawnFloatingTextEvent::new(
+                        "Combo lost!",
+                        position,
+                        Color::RED,
+                    ));
+                }
                 score_system.reset_multiplier();
             }
         }
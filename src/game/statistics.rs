@@ -0,0 +1,106 @@
+use bevy::{prelude::*, utils::HashMap};
+use std::time::Duration;
+
+use crate::{playing, AppState};
+
+use super::{
+    combat::{DamageEvent, EnemyKilledEvent, PlayerHitEvent},
+    enemy::EnemyKind,
+    power_up::PowerUpCollectedEvent,
+    score_system::Score,
+    Player,
+};
+
+pub(super) struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStatistics>();
+
+        app.add_systems(OnEnter(AppState::InGame), reset_run_statistics);
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                track_time_survived,
+                track_damage,
+                track_kills,
+                track_powerups,
+                track_multiplier_streak,
+            )
+                .run_if(playing()),
+        );
+    }
+}
+
+/// Everything that happened during the current run.
+#[derive(Resource, Default, Debug)]
+pub struct RunStatistics {
+    pub buildings_destroyed: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub fire_spent: u32,
+    pub kills: HashMap<EnemyKind, u32>,
+    pub longest_multiplier_streak: i16,
+    pub powerups_collected: u32,
+    pub time_survived: Duration,
+}
+
+impl RunStatistics {
+    pub fn kills_of(&self, kind: EnemyKind) -> u32 {
+        self.kills.get(&kind).copied().unwrap_or_default()
+    }
+}
+
+fn reset_run_statistics(mut run_statistics: ResMut<RunStatistics>) {
+    *run_statistics = RunStatistics::default();
+}
+
+fn track_time_survived(mut run_statistics: ResMut<RunStatistics>, time: Res<Time>) {
+    run_statistics.time_survived += time.delta();
+}
+
+fn track_damage(
+    mut run_statistics: ResMut<RunStatistics>,
+    mut damage_event_reader: EventReader<DamageEvent>,
+    mut player_hit_event_reader: EventReader<PlayerHitEvent>,
+) {
+    for event in damage_event_reader.read() {
+        run_statistics.damage_dealt += event.amount as u32;
+    }
+
+    for event in player_hit_event_reader.read() {
+        run_statistics.damage_taken += event.damage as u32;
+    }
+}
+
+fn track_kills(
+    mut run_statistics: ResMut<RunStatistics>,
+    mut enemy_killed_event_reader: EventReader<EnemyKilledEvent>,
+) {
+    for event in enemy_killed_event_reader.read() {
+        if event.kind == EnemyKind::Building {
+            run_statistics.buildings_destroyed += 1;
+        } else {
+            *run_statistics.kills.entry(event.kind).or_default() += 1;
+        }
+    }
+}
+
+fn track_powerups(
+    mut run_statistics: ResMut<RunStatistics>,
+    mut powerup_collected_event_reader: EventReader<PowerUpCollectedEvent>,
+) {
+    run_statistics.powerups_collected += powerup_collected_event_reader.read().count() as u32;
+}
+
+fn track_multiplier_streak(
+    mut run_statistics: ResMut<RunStatistics>,
+    player_query: Query<&Score, (Changed<Score>, With<Player>)>,
+) {
+    if let Ok(score) = player_query.get_single() {
+        run_statistics.longest_multiplier_streak = run_statistics
+            .longest_multiplier_streak
+            .max(score.multiplier());
+    }
+}
@@ -1,29 +1,53 @@
-use std::{f32::consts::FRAC_PI_2, time::Duration};
+use std::{f32::consts::FRAC_PI_3, time::Duration};
 
 use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
-use bevy_kira_audio::{Audio, AudioChannel, AudioControl};
-use bevy_rapier2d::prelude::Collider;
+use bevy_rapier2d::prelude::{Collider, Velocity};
 
 use crate::{
     animation::AnimationTimer,
-    audio::DragonBreathChannel,
+    audio::BreathAudio,
     camera::MainCamera,
-    game::{Fire, Player, ResourcePool, SpawnFireBreathEvent},
+    game::{
+        AbilityKind, Altitude, Banking, BreathCharge, BreathShape, ChargedBreathEvent, Dashing,
+        Fire, Netted, Overheated, Player, ResourcePool, Slowed, SpawnFireBreathEvent,
+        UnlockedBreathShapes, UseAbilityEvent,
+    },
     physics::Speed,
-    playing, AppState,
+    playing, AppState, InGameState,
 };
 
+use self::action::ActionPlugin;
+pub use self::action::{Action, ActionState, ActionSystem, InputBinding, InputBindings};
+
+mod action;
+
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(ActionPlugin);
+
+        app.init_resource::<ControlScheme>();
+
         app.add_systems(
             PreUpdate,
             (
-                clear_input.run_if(state_changed::<AppState>),
-                (mouse_input, player_movement).run_if(playing()),
-            )
-                .chain(),
+                clear_input
+                    .run_if(state_changed::<AppState>.or_else(state_changed::<InGameState>))
+                    .before(ActionSystem),
+                (
+                    control_scheme_input,
+                    breath_shape_input,
+                    altitude_input,
+                    ability_input,
+                    player_movement,
+                    breath_input,
+                    charge_input,
+                )
+                    .chain()
+                    .after(ActionSystem)
+                    .run_if(playing()),
+            ),
         );
     }
 }
@@ -31,6 +55,17 @@ impl Plugin for InputPlugin {
 #[derive(Resource)]
 struct CursorWorldPosition(Option<Vec2>);
 
+/// How the dragon is steered and where its breath is aimed.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// The dragon flies towards the cursor and breathes where it's flying.
+    #[default]
+    FollowCursor,
+    /// The dragon flies with the keyboard (or towards the cursor while the right button is held)
+    /// and aims its breath at the cursor.
+    Strafe,
+}
+
 #[derive(SystemParam)]
 pub struct CursorWorldPositionChecker<'w, 's> {
     window_query: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
@@ -44,105 +79,335 @@ pub struct CursorWorldPositionChecker<'w, 's> {
 
 impl CursorWorldPositionChecker<'_, '_> {
     pub fn cursor_world_position(&self) -> Option<Vec2> {
-        let window = self.window_query.single();
+        let window = self.window_query.get_single().ok()?;
+
+        window
+            .cursor_position()
+            .and_then(|cursor_position| self.viewport_to_world_position(cursor_position))
+    }
+
+    /// Converts a position on the window, like a touch, to a position in the world.
+    pub fn viewport_to_world_position(&self, viewport_position: Vec2) -> Option<Vec2> {
+        let (camera, camera_transform) = self.camera_query.get_single().ok()?;
 
-        window.cursor_position().and_then(|cursor_position| {
-            let (camera, camera_transform) = self.camera_query.single();
-            camera.viewport_to_world_2d(camera_transform, cursor_position)
-        })
+        camera.viewport_to_world_2d(camera_transform, viewport_position)
     }
 }
 
 #[derive(Component)]
 struct FireBreathSfx;
 
-fn mouse_input(
+fn breath_input(
     mut spawn_fire_breath_event_writer: EventWriter<SpawnFireBreathEvent>,
-    query: Query<(&Transform, &ResourcePool<Fire>), With<Player>>,
-    asset_server: Res<AssetServer>,
-    mouse_input: ResMut<ButtonInput<MouseButton>>,
-    dragon_breath_audio_channel: Res<AudioChannel<DragonBreathChannel>>,
-    audio: Res<Audio>,
+    query: Query<(&Transform, &ResourcePool<Fire>, Has<Overheated>), With<Player>>,
+    action_state: Res<ActionState>,
+    mut breath_audio: BreathAudio,
+    control_scheme: Res<ControlScheme>,
 ) {
-    let (player_transform, fire_breath_resource_pool) = query.single();
+    let (player_transform, fire_breath_resource_pool, overheated) = query.single();
 
-    if mouse_input.just_pressed(MouseButton::Left) {
-        dragon_breath_audio_channel.play(
-            asset_server
-                .get_handle("sfx/breathstart.ogg")
-                .unwrap_or_default(),
-        );
-        dragon_breath_audio_channel
-            .play(
-                asset_server
-                    .get_handle("sfx/breathloop.ogg")
-                    .unwrap_or_default(),
-            )
-            .looped();
-    } else if mouse_input.just_released(MouseButton::Left) {
-        if !fire_breath_resource_pool.is_empty() {
-            audio.play(
-                asset_server
-                    .get_handle("sfx/breathend.ogg")
-                    .unwrap_or_default(),
-            );
+    if overheated {
+        return;
+    }
+
+    if action_state.just_pressed(Action::Breathe) {
+        breath_audio.start();
+    } else if action_state.just_released(Action::Breathe) {
+        if fire_breath_resource_pool.is_empty() {
+            breath_audio.stop();
+        } else {
+            breath_audio.end();
         }
-        dragon_breath_audio_channel.stop();
     }
 
-    if mouse_input.pressed(MouseButton::Left) {
-        let player_direction = player_transform.rotation.mul_vec3(Vec3::Y).truncate();
-        // TODO: replace constant with sprite dimensions
-        let fire_position = player_transform.translation.truncate() + player_direction * 90.;
+    if action_state.pressed(Action::Breathe) {
+        let (player_direction, fire_position) =
+            breath_origin(player_transform, &control_scheme, &action_state);
 
         if !fire_breath_resource_pool.is_empty() {
-            spawn_fire_breath_event_writer.send(SpawnFireBreathEvent::new(1, fire_position));
+            spawn_fire_breath_event_writer.send(SpawnFireBreathEvent::new(
+                1,
+                player_direction,
+                fire_position,
+            ));
+        }
+    }
+}
+
+fn charge_input(
+    mut charged_breath_event_writer: EventWriter<ChargedBreathEvent>,
+    mut query: Query<
+        (
+            &Transform,
+            &ResourcePool<Fire>,
+            &mut BreathCharge,
+            Has<Overheated>,
+        ),
+        With<Player>,
+    >,
+    action_state: Res<ActionState>,
+    mut breath_audio: BreathAudio,
+    control_scheme: Res<ControlScheme>,
+    time: Res<Time>,
+) {
+    const FULL_CHARGE_SECONDS: f32 = 1.5;
+
+    let (player_transform, fire_breath_resource_pool, mut breath_charge, overheated) =
+        query.single_mut();
+    let can_charge = !fire_breath_resource_pool.is_empty() && !overheated;
+
+    if action_state.just_pressed(Action::ChargeBreath) && can_charge {
+        breath_audio.start_charge();
+    }
+
+    if action_state.pressed(Action::ChargeBreath) && can_charge {
+        breath_charge.0 = (breath_charge.0 + time.delta_seconds() / FULL_CHARGE_SECONDS).min(1.);
+        breath_audio.set_charge(breath_charge.0);
+    } else if breath_charge.0 > 0. {
+        let (player_direction, fire_position) =
+            breath_origin(player_transform, &control_scheme, &action_state);
+
+        charged_breath_event_writer.send(ChargedBreathEvent::new(
+            breath_charge.0,
+            player_direction,
+            fire_position,
+        ));
+        breath_charge.0 = 0.;
+        breath_audio.end_charge();
+    }
+}
+
+/// Returns the direction the breath is aimed at and the position it comes out from.
+fn breath_origin(
+    player_transform: &Transform,
+    control_scheme: &ControlScheme,
+    action_state: &ActionState,
+) -> (Vec2, Vec2) {
+    const MAX_AIM_ANGLE: f32 = FRAC_PI_3;
+
+    let player_position = player_transform.translation.truncate();
+    let mut player_direction = player_transform.rotation.mul_vec3(Vec3::Y).truncate();
+
+    // The right stick aims in any control scheme, the cursor only while strafing.
+    let aim = action_state.aim().or_else(|| {
+        action_state
+            .cursor()
+            .filter(|_| *control_scheme == ControlScheme::Strafe)
+            .map(|cursor_position| cursor_position - player_position)
+    });
+
+    if let Some(aim) = aim {
+        player_direction = clamp_to_arc(player_direction, aim, MAX_AIM_ANGLE);
+    }
+
+    // TODO: replace constant with sprite dimensions
+    (player_direction, player_position + player_direction * 90.)
+}
+
+fn breath_shape_input(
+    mut query: Query<(&mut BreathShape, &UnlockedBreathShapes), With<Player>>,
+    action_state: Res<ActionState>,
+) {
+    let Some(new_breath_shape) = [BreathShape::Cone, BreathShape::Beam, BreathShape::Fireball]
+        .into_iter()
+        .find(|&breath_shape| action_state.just_pressed(Action::SelectBreath(breath_shape)))
+    else {
+        return;
+    };
+
+    let (mut breath_shape, unlocked_breath_shapes) = query.single_mut();
+
+    if *breath_shape != new_breath_shape && unlocked_breath_shapes.contains(&new_breath_shape) {
+        *breath_shape = new_breath_shape;
+    }
+}
+
+fn altitude_input(
+    mut query: Query<&mut Altitude, (With<Player>, Without<Netted>)>,
+    action_state: Res<ActionState>,
+) {
+    if action_state.just_pressed(Action::ToggleAltitude) {
+        let Ok(mut altitude) = query.get_single_mut() else {
+            return;
+        };
+
+        *altitude = match *altitude {
+            Altitude::High => Altitude::Low,
+            Altitude::Low => Altitude::High,
+        };
+    }
+}
+
+fn ability_input(
+    mut use_ability_event_writer: EventWriter<UseAbilityEvent>,
+    action_state: Res<ActionState>,
+) {
+    for ability_kind in AbilityKind::ALL {
+        if action_state.just_pressed(Action::Ability(ability_kind)) {
+            use_ability_event_writer.send(UseAbilityEvent::new(ability_kind));
         }
     }
 }
 
 fn player_movement(
-    mut query: Query<(&mut Transform, &Speed, &mut AnimationTimer, &Collider), With<Player>>,
-    cursor_world_position_checker: CursorWorldPositionChecker,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Banking,
+            &Speed,
+            &mut AnimationTimer,
+            &Collider,
+            Option<&Slowed>,
+            Has<Netted>,
+        ),
+        (With<Player>, Without<Dashing>),
+    >,
+    action_state: Res<ActionState>,
+    control_scheme: Res<ControlScheme>,
+    time: Res<Time>,
 ) {
-    if let Some(cursor_position) = cursor_world_position_checker.cursor_world_position() {
-        let (mut player_transform, player_speed, mut player_animation_timer, player_collider) =
-            query.single_mut();
-        let player_position = player_transform.translation.truncate();
-        let cursor_to_player_vector = cursor_position - player_position;
+    // Units per second squared the dragon gains at full throttle.
+    const ACCELERATION: f32 = 1200.;
+    // Fraction of the velocity lost every second.
+    const DRAG: f32 = 1.5;
+    // Fraction of the speed above the top speed lost every second.
+    const OVERSPEED_DRAG: f32 = 8.;
+    // Radians per second the dragon can turn at most.
+    const MAX_TURN_RATE: f32 = 4.;
+    // How quickly the banking catches up with the turn.
+    const BANKING_RATE: f32 = 6.;
 
-        if cursor_to_player_vector != Vec2::ZERO {
-            let cursor_distance_to_player = cursor_position.distance(player_position);
-            let velocity_rate = cursor_distance_to_player.min(300.) / 300.;
-            let direction = cursor_to_player_vector.normalize();
+    // The query is empty while the dragon is dashing, since it can't be steered then.
+    let Ok((
+        mut player_transform,
+        mut player_velocity,
+        mut player_banking,
+        player_speed,
+        mut player_animation_timer,
+        player_collider,
+        player_slowed,
+        player_netted,
+    )) = query.get_single_mut()
+    else {
+        return;
+    };
+    let delta_seconds = time.delta_seconds();
+    let max_speed = if player_netted {
+        0.
+    } else {
+        player_speed.0 * player_slowed.map_or(1., |slowed| slowed.factor)
+    };
+    let player_position = player_transform.translation.truncate();
+    let follow_cursor = match *control_scheme {
+        ControlScheme::FollowCursor => true,
+        ControlScheme::Strafe => action_state.pressed(Action::SteerToCursor),
+    };
+    let movement = action_state.movement();
 
+    // The direction the dragon wants to face and how hard it should fly towards it. The keys
+    // and the left stick win over the cursor.
+    let (direction, throttle) = if let Some(cursor_position) = action_state
+        .cursor()
+        .filter(|_| follow_cursor && movement == Vec2::ZERO)
+    {
+        let cursor_to_player_vector = cursor_position - player_position;
+        let cursor_distance_to_player = cursor_to_player_vector.length();
+        let throttle =
             if cursor_distance_to_player > player_collider.as_cuboid().unwrap().half_extents().y {
-                player_transform.translation.x += direction.x * player_speed.0 * velocity_rate;
-                player_transform.translation.y += direction.y * player_speed.0 * velocity_rate;
-                player_animation_timer.set_duration(Duration::from_secs_f32(
-                    0.2 * player_speed.0 * 0.25 * velocity_rate,
-                ));
+                cursor_distance_to_player.min(300.) / 300.
             } else {
-                player_animation_timer.set_duration(Duration::from_secs_f32(0.2));
-            }
+                0.
+            };
 
-            if direction != Vec2::ZERO {
-                let angle = (direction).angle_between(Vec2::X);
+        (cursor_to_player_vector.normalize_or_zero(), throttle)
+    } else {
+        (movement.normalize_or_zero(), movement.length())
+    };
 
-                if angle.is_finite() {
-                    // FIXME: Rotate the image sprite to always face right?
-                    // FRAC_PI_2 is subtracted to offset the 90 degree rotation from the X axis the sprite has.
-                    player_transform.rotation = Quat::from_rotation_z(-angle - FRAC_PI_2);
-                }
+    let facing = player_transform.rotation.mul_vec3(Vec3::Y).truncate();
+    let mut turn = 0.;
+
+    if direction != Vec2::ZERO {
+        let angle = facing.angle_between(direction);
+
+        if angle.is_finite() {
+            let max_turn = MAX_TURN_RATE * delta_seconds;
+            turn = angle.clamp(-max_turn, max_turn);
+            player_transform.rotate_z(turn);
+
+            if max_turn > 0. {
+                turn /= max_turn;
             }
         }
     }
+
+    // The dragon only pushes forward, so sharp turns have to bleed off the old momentum first.
+    let facing = player_transform.rotation.mul_vec3(Vec3::Y).truncate();
+    let mut linvel = player_velocity.linvel;
+
+    // Throttle can't push the dragon past its top speed, but momentum above it from a knockback
+    // or a dash isn't cut off either, it bleeds off quickly instead.
+    if max_speed > 0. {
+        linvel = (linvel + facing * ACCELERATION * throttle * delta_seconds)
+            .clamp_length_max(linvel.length().max(max_speed));
+    }
+
+    linvel *= (1. - DRAG * delta_seconds).max(0.);
+
+    let speed = linvel.length();
+
+    if speed > max_speed {
+        let overspeed = (speed - max_speed) * (1. - OVERSPEED_DRAG * delta_seconds).max(0.);
+        linvel = linvel.normalize_or_zero() * (max_speed + overspeed);
+    }
+
+    player_velocity.linvel = linvel;
+
+    player_banking.0 += (turn - player_banking.0) * (BANKING_RATE * delta_seconds).min(1.);
+
+    let speed_rate = if max_speed > 0. {
+        (player_velocity.linvel.length() / max_speed).min(1.)
+    } else {
+        0.
+    };
+
+    // Wings beat faster while accelerating and slow down to a glide at cruising speed.
+    player_animation_timer.set_duration(Duration::from_secs_f32(
+        0.2 - 0.1 * throttle * (1. - speed_rate),
+    ));
+}
+
+fn control_scheme_input(mut control_scheme: ResMut<ControlScheme>, action_state: Res<ActionState>) {
+    if action_state.just_pressed(Action::ToggleControlScheme) {
+        *control_scheme = match *control_scheme {
+            ControlScheme::FollowCursor => ControlScheme::Strafe,
+            ControlScheme::Strafe => ControlScheme::FollowCursor,
+        };
+    }
+}
+
+/// Rotates `target` towards `facing` so that it's at most `max_angle` radians away from it.
+fn clamp_to_arc(facing: Vec2, target: Vec2, max_angle: f32) -> Vec2 {
+    let angle = facing.angle_between(target);
+
+    if angle.is_finite() {
+        Vec2::from_angle(angle.clamp(-max_angle, max_angle)).rotate(facing)
+    } else {
+        facing
+    }
 }
 
 fn clear_input(
     mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
     mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut gamepad_input: ResMut<ButtonInput<GamepadButton>>,
+    mut touches: ResMut<Touches>,
+    mut action_state: ResMut<ActionState>,
 ) {
     keyboard_input.reset_all();
-    mouse_input.reset_all()
+    mouse_input.reset_all();
+    gamepad_input.reset_all();
+    touches.reset_all();
+    action_state.reset();
 }
@@ -0,0 +1,478 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::{mouse::MouseMotion, InputSystem},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{AbilityKind, BreathShape},
+    storage,
+};
+
+use super::CursorWorldPositionChecker;
+
+const INPUT_BINDINGS_KEY: &str = "controls";
+
+pub(super) struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>();
+
+        // Saved bindings are laid over the defaults, so actions added since they were saved
+        // still get a binding.
+        let mut input_bindings = InputBindings::default();
+
+        if let Some(saved_input_bindings) = storage::load::<InputBindings>(INPUT_BINDINGS_KEY) {
+            input_bindings.extend(saved_input_bindings.0);
+        }
+
+        app.insert_resource(input_bindings);
+
+        app.add_systems(
+            PreUpdate,
+            update_action_state.in_set(ActionSystem).after(InputSystem),
+        );
+    }
+}
+
+/// Systems that turn raw device input into [`ActionState`]. Anything reading actions in
+/// `PreUpdate` should run after this set.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+/// Something the player can do, independently of the device used to do it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Breathe,
+    ChargeBreath,
+    Ability(AbilityKind),
+    SelectBreath(BreathShape),
+    ToggleAltitude,
+    ToggleControlScheme,
+    /// Flies towards the cursor while held, in the strafe control scheme.
+    SteerToCursor,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Self; 16] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Breathe,
+        Self::ChargeBreath,
+        Self::Ability(AbilityKind::Dash),
+        Self::Ability(AbilityKind::TailSwipe),
+        Self::Ability(AbilityKind::Roar),
+        Self::SelectBreath(BreathShape::Cone),
+        Self::SelectBreath(BreathShape::Beam),
+        Self::SelectBreath(BreathShape::Fireball),
+        Self::ToggleAltitude,
+        Self::ToggleControlScheme,
+        Self::SteerToCursor,
+        Self::Pause,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::Breathe => "Breathe",
+            Self::ChargeBreath => "Charge Breath",
+            Self::Ability(AbilityKind::Dash) => "Dash",
+            Self::Ability(AbilityKind::TailSwipe) => "Tail Swipe",
+            Self::Ability(AbilityKind::Roar) => "Roar",
+            Self::SelectBreath(BreathShape::Cone) => "Cone Breath",
+            Self::SelectBreath(BreathShape::Beam) => "Beam Breath",
+            Self::SelectBreath(BreathShape::Fireball) => "Fireball Breath",
+            Self::ToggleAltitude => "Change Altitude",
+            Self::ToggleControlScheme => "Change Control Scheme",
+            Self::SteerToCursor => "Steer to Cursor",
+            Self::Pause => "Pause",
+        }
+    }
+}
+
+/// A single button on any device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl InputBinding {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Self::Gamepad(_))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+
+                name.trim_start_matches("Key")
+                    .trim_start_matches("Digit")
+                    .to_string()
+            }
+            Self::Mouse(button) => format!("Mouse {button:?}"),
+            Self::Gamepad(button_type) => format!("{button_type:?}"),
+        }
+    }
+}
+
+/// The buttons bound to every action. The mouse and the gamepad sticks always steer and aim
+/// as well.
+#[derive(Resource, Deref, DerefMut, Serialize, Deserialize)]
+pub struct InputBindings(HashMap<Action, Vec<InputBinding>>);
+
+impl InputBindings {
+    pub fn save(&self) {
+        storage::save(INPUT_BINDINGS_KEY, self);
+    }
+
+    /// Replaces the action's bindings on the new binding's device. A button only ever triggers
+    /// one action, so whichever action had it takes over the replaced binding instead.
+    pub fn rebind(&mut self, action: Action, new_binding: InputBinding) {
+        let same_device = |binding: &InputBinding| binding.is_gamepad() == new_binding.is_gamepad();
+
+        let bindings = self.entry(action).or_default();
+        let old_binding = bindings.iter().copied().find(same_device);
+
+        bindings.retain(|binding| !same_device(binding));
+        bindings.push(new_binding);
+
+        for (_, other_bindings) in self
+            .iter_mut()
+            .filter(|(&other_action, _)| other_action != action)
+        {
+            if let Some(index) = other_bindings
+                .iter()
+                .position(|&binding| binding == new_binding)
+            {
+                match old_binding.filter(|old_binding| !other_bindings.contains(old_binding)) {
+                    Some(old_binding) => other_bindings[index] = old_binding,
+                    None => {
+                        other_bindings.remove(index);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputBinding::*;
+
+        Self(HashMap::from([
+            (
+                Action::MoveUp,
+                vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp)],
+            ),
+            (
+                Action::MoveDown,
+                vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown)],
+            ),
+            (
+                Action::MoveLeft,
+                vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft)],
+            ),
+            (
+                Action::MoveRight,
+                vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight)],
+            ),
+            (
+                Action::Breathe,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Key(KeyCode::KeyF),
+                    Gamepad(GamepadButtonType::RightTrigger2),
+                ],
+            ),
+            (
+                Action::ChargeBreath,
+                vec![
+                    Key(KeyCode::Space),
+                    Gamepad(GamepadButtonType::RightTrigger),
+                ],
+            ),
+            (
+                Action::Ability(AbilityKind::Dash),
+                vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButtonType::South)],
+            ),
+            (
+                Action::Ability(AbilityKind::TailSwipe),
+                vec![Key(KeyCode::KeyE), Gamepad(GamepadButtonType::West)],
+            ),
+            (
+                Action::Ability(AbilityKind::Roar),
+                vec![Key(KeyCode::KeyR), Gamepad(GamepadButtonType::North)],
+            ),
+            (
+                Action::SelectBreath(BreathShape::Cone),
+                vec![Key(KeyCode::Digit1), Gamepad(GamepadButtonType::DPadLeft)],
+            ),
+            (
+                Action::SelectBreath(BreathShape::Beam),
+                vec![Key(KeyCode::Digit2), Gamepad(GamepadButtonType::DPadUp)],
+            ),
+            (
+                Action::SelectBreath(BreathShape::Fireball),
+                vec![Key(KeyCode::Digit3), Gamepad(GamepadButtonType::DPadRight)],
+            ),
+            (
+                Action::ToggleAltitude,
+                vec![Key(KeyCode::KeyQ), Gamepad(GamepadButtonType::LeftTrigger)],
+            ),
+            (
+                Action::ToggleControlScheme,
+                vec![Key(KeyCode::Tab), Gamepad(GamepadButtonType::Select)],
+            ),
+            (Action::SteerToCursor, vec![Mouse(MouseButton::Right)]),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)],
+            ),
+        ]))
+    }
+}
+
+/// What the player is doing this frame, gathered from every device.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+    movement: Vec2,
+    aim: Option<Vec2>,
+    cursor: Option<Vec2>,
+    cursor_active: bool,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    /// Direction the player wants to fly in from the keys or the left stick. Its length is the
+    /// throttle, from 0 to 1.
+    pub fn movement(&self) -> Vec2 {
+        self.movement
+    }
+
+    /// Direction the right stick is pushed in, if it is.
+    pub fn aim(&self) -> Option<Vec2> {
+        self.aim
+    }
+
+    /// World position of the cursor or the steering finger, unless the player has moved on to
+    /// the keyboard or a gamepad since last touching the mouse.
+    pub fn cursor(&self) -> Option<Vec2> {
+        self.cursor.filter(|_| self.cursor_active)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            cursor_active: self.cursor_active,
+            ..default()
+        };
+    }
+}
+
+/// The raw state of every device the actions can be bound to.
+#[derive(SystemParam)]
+struct InputDevices<'w> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    mouse_input: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_input: Res<'w, ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+    touches: Res<'w, Touches>,
+}
+
+fn update_action_state(
+    mut action_state: ResMut<ActionState>,
+    mut mouse_motion_event_reader: EventReader<MouseMotion>,
+    input_bindings: Res<InputBindings>,
+    input_devices: InputDevices,
+    cursor_world_position_checker: CursorWorldPositionChecker,
+) {
+    const STICK_DEAD_ZONE: f32 = 0.2;
+
+    let InputDevices {
+        keyboard_input,
+        mouse_input,
+        gamepads,
+        gamepad_input,
+        gamepad_axes,
+        touches,
+    } = &input_devices;
+
+    let binding_pressed = |binding: &InputBinding| match *binding {
+        InputBinding::Key(key) => keyboard_input.pressed(key),
+        InputBinding::Mouse(button) => mouse_input.pressed(button),
+        InputBinding::Gamepad(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_input.pressed(GamepadButton::new(gamepad, button_type))),
+    };
+    let stick = |x_axis: GamepadAxisType, y_axis: GamepadAxisType| {
+        gamepads
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    gamepad_axes
+                        .get(GamepadAxis::new(gamepad, x_axis))
+                        .unwrap_or_default(),
+                    gamepad_axes
+                        .get(GamepadAxis::new(gamepad, y_axis))
+                        .unwrap_or_default(),
+                )
+            })
+            .find(|direction| direction.length() > STICK_DEAD_ZONE)
+    };
+
+    let ActionState {
+        pressed,
+        just_pressed,
+        just_released,
+        ..
+    } = &mut *action_state;
+    let previously_pressed = std::mem::take(pressed);
+
+    for (&action, bindings) in input_bindings.iter() {
+        if bindings.iter().any(binding_pressed) {
+            pressed.insert(action);
+        }
+    }
+
+    // On touch screens the first finger steers the dragon and any other finger breathes.
+    let steering_touch = touches.iter().min_by_key(|touch| touch.id());
+
+    if let Some(steering_touch) = steering_touch {
+        pressed.insert(Action::SteerToCursor);
+
+        if touches
+            .iter()
+            .any(|touch| touch.id() != steering_touch.id())
+        {
+            pressed.insert(Action::Breathe);
+        }
+    }
+
+    *just_pressed = pressed.difference(&previously_pressed).copied().collect();
+    *just_released = previously_pressed.difference(pressed).copied().collect();
+
+    let mouse_used = mouse_motion_event_reader.read().count() > 0
+        || mouse_input.get_just_pressed().next().is_some();
+    let keyboard_movement = pressed_direction(&action_state.pressed);
+    let stick_movement = stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY);
+    let stick_aim = stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY);
+
+    action_state.movement =
+        stick_movement.map_or(keyboard_movement, |movement| movement.clamp_length_max(1.));
+    action_state.aim = stick_aim.map(Vec2::normalize);
+
+    if let Some(steering_touch) = steering_touch {
+        action_state.cursor =
+            cursor_world_position_checker.viewport_to_world_position(steering_touch.position());
+        action_state.cursor_active = true;
+        return;
+    }
+
+    action_state.cursor = cursor_world_position_checker.cursor_world_position();
+
+    // The cursor stays on screen when the player switches to the keys or a gamepad, so it
+    // only steers the dragon until one of those is used. Lifting the steering finger lets the
+    // dragon glide instead of flying on towards where the finger was.
+    if touches.any_just_released() {
+        action_state.cursor_active = false;
+    } else if mouse_used {
+        action_state.cursor_active = true;
+    } else if keyboard_movement != Vec2::ZERO || stick_movement.is_some() || stick_aim.is_some() {
+        action_state.cursor_active = false;
+    }
+}
+
+fn pressed_direction(pressed: &HashSet<Action>) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+
+    if pressed.contains(&Action::MoveUp) {
+        direction.y += 1.;
+    }
+    if pressed.contains(&Action::MoveDown) {
+        direction.y -= 1.;
+    }
+    if pressed.contains(&Action::MoveRight) {
+        direction.x += 1.;
+    }
+    if pressed.contains(&Action::MoveLeft) {
+        direction.x -= 1.;
+    }
+
+    direction.normalize_or_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebind_swaps_the_replaced_binding_onto_the_conflicting_action() {
+        let mut input_bindings = InputBindings::default();
+
+        input_bindings.rebind(Action::MoveUp, InputBinding::Key(KeyCode::KeyS));
+
+        assert_eq!(
+            input_bindings[&Action::MoveUp],
+            [InputBinding::Key(KeyCode::KeyS)]
+        );
+        assert_eq!(
+            input_bindings[&Action::MoveDown],
+            [
+                InputBinding::Key(KeyCode::KeyW),
+                InputBinding::Key(KeyCode::ArrowDown),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebind_removes_the_conflicting_binding_if_there_is_nothing_to_swap() {
+        let mut input_bindings = InputBindings::default();
+
+        input_bindings.rebind(
+            Action::SteerToCursor,
+            InputBinding::Gamepad(GamepadButtonType::South),
+        );
+
+        assert_eq!(
+            input_bindings[&Action::SteerToCursor],
+            [
+                InputBinding::Mouse(MouseButton::Right),
+                InputBinding::Gamepad(GamepadButtonType::South),
+            ]
+        );
+        assert_eq!(
+            input_bindings[&Action::Ability(AbilityKind::Dash)],
+            [InputBinding::Key(KeyCode::ShiftLeft)]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{audio::PlayMusicEvent, entity_cleanup, storage, AppState};
+
+const HOARD_KEY: &str = "hoard";
+
+pub struct LairPlugin;
+
+impl Plugin for LairPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Hoard>(HOARD_KEY).unwrap_or_default());
+
+        app.add_systems(OnEnter(AppState::Lair), (setup_lair, play_background_music));
+
+        // Rebuild the screen whenever a purchase or skin change touches the hoard.
+        app.add_systems(
+            Update,
+            (
+                handle_lair_button_interactions,
+                (entity_cleanup::<With<LairEntity>>, setup_lair)
+                    .chain()
+                    .run_if(resource_changed::<Hoard>),
+            )
+                .chain()
+                .run_if(in_state(AppState::Lair)),
+        );
+
+        app.add_systems(OnExit(AppState::Lair), entity_cleanup::<With<LairEntity>>);
+    }
+}
+
+/// The dragon's gold, kept between runs and spent on unlocks in the Lair.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Hoard {
+    pub gold: u32,
+    pub skin: DragonSkin,
+    pub unlocks: HashSet<Unlock>,
+}
+
+impl Hoard {
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocks.contains(&unlock)
+    }
+
+    pub fn save(&self) {
+        storage::save(HOARD_KEY, self);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DragonSkin {
+    #[default]
+    Crimson,
+    Emerald,
+    Obsidian,
+    Golden,
+}
+
+impl DragonSkin {
+    /// Tint applied on top of the dragon's sprite.
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Crimson => Color::WHITE,
+            Self::Emerald => Color::rgb(0.5, 1., 0.6),
+            Self::Obsidian => Color::rgb(0.45, 0.4, 0.5),
+            Self::Golden => Color::rgb(1., 0.85, 0.3),
+        }
+    }
+}
+
+/// Something that can be bought in the Lair. Unlocks are permanent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unlock {
+    ToughScales,
+    DeepLungs,
+    SwiftWings,
+    BeamBreath,
+    FireballBreath,
+    Skin(DragonSkin),
+}
+
+impl Unlock {
+    const ALL: [Self; 8] = [
+        Self::ToughScales,
+        Self::DeepLungs,
+        Self::SwiftWings,
+        Self::BeamBreath,
+        Self::FireballBreath,
+        Self::Skin(DragonSkin::Emerald),
+        Self::Skin(DragonSkin::Obsidian),
+        Self::Skin(DragonSkin::Golden),
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ToughScales => "Tough Scales: +25 maximum health",
+            Self::DeepLungs => "Deep Lungs: +25 maximum fire",
+            Self::SwiftWings => "Swift Wings: +10% flight speed",
+            Self::BeamBreath => "Beam Breath",
+            Self::FireballBreath => "Fireball Breath",
+            Self::Skin(DragonSkin::Crimson) => "Crimson Skin",
+            Self::Skin(DragonSkin::Emerald) => "Emerald Skin",
+            Self::Skin(DragonSkin::Obsidian) => "Obsidian Skin",
+            Self::Skin(DragonSkin::Golden) => "Golden Skin",
+        }
+    }
+
+    fn cost(&self) -> u32 {
+        match self {
+            Self::ToughScales | Self::DeepLungs => 100,
+            Self::SwiftWings => 150,
+            Self::BeamBreath => 200,
+            Self::FireballBreath => 300,
+            Self::Skin(DragonSkin::Golden) => 500,
+            Self::Skin(_) => 50,
+        }
+    }
+}
+
+#[derive(Component)]
+struct LairEntity;
+
+#[derive(Component)]
+enum LairButtonAction {
+    Buy(Unlock),
+    Wear(DragonSkin),
+    Back,
+}
+
+fn setup_lair(mut commands: Commands, asset_server: Res<AssetServer>, hoard: Res<Hoard>) {
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+    let owned_skins =
+        std::iter::once(DragonSkin::Crimson).chain(hoard.unlocks.iter().filter_map(|unlock| {
+            match unlock {
+                Unlock::Skin(skin) => Some(*skin),
+                _ => None,
+            }
+        }));
+
+    commands
+        .spawn((
+            LairEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::rgb(0.1, 0.05, 0.05)),
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|node| {
+            node.spawn(TextBundle::from_section(
+                "Lair",
+                TextStyle {
+                    color: Color::WHITE,
+                    font: font.clone(),
+                    font_size: 64.,
+                },
+            ));
+
+            node.spawn(TextBundle::from_section(
+                format!("Hoard: {} gold", hoard.gold),
+                TextStyle {
+                    color: Color::GOLD,
+                    font: font.clone(),
+                    font_size: 32.,
+                },
+            ));
+
+            for unlock in Unlock::ALL {
+                let (label, action) = if hoard.has(unlock) {
+                    (format!("{} (owned)", unlock.name()), None)
+                } else {
+                    (
+                        format!("{} - {} gold", unlock.name(), unlock.cost()),
+                        Some(LairButtonAction::Buy(unlock)),
+                    )
+                };
+
+                spawn_lair_button(node, &font, label, action);
+            }
+
+            for skin in owned_skins {
+                let label = if hoard.skin == skin {
+                    format!("Wearing {}", Unlock::Skin(skin).name())
+                } else {
+                    format!("Wear {}", Unlock::Skin(skin).name())
+                };
+
+                spawn_lair_button(node, &font, label, Some(LairButtonAction::Wear(skin)));
+            }
+
+            spawn_lair_button(
+                node,
+                &font,
+                "Back to Menu".to_string(),
+                Some(LairButtonAction::Back),
+            );
+        });
+}
+
+fn spawn_lair_button(
+    builder: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: String,
+    action: Option<LairButtonAction>,
+) {
+    let mut button = builder.spawn(ButtonBundle {
+        background_color: if action.is_some() {
+            Color::ALICE_BLUE.into()
+        } else {
+            Color::GRAY.into()
+        },
+        style: Style {
+            padding: UiRect::horizontal(Val::Px(8.)),
+            ..default()
+        },
+        ..default()
+    });
+
+    if let Some(action) = action {
+        button.insert(action);
+    }
+
+    button.with_children(|button| {
+        button.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                color: Color::BLACK,
+                font: font.clone(),
+                font_size: 24.0,
+            },
+        ));
+    });
+}
+
+fn handle_lair_button_interactions(
+    mut app_state: ResMut<NextState<AppState>>,
+    mut hoard: ResMut<Hoard>,
+    query: Query<(&Interaction, &LairButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, lair_button_action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *lair_button_action {
+            LairButtonAction::Buy(unlock) => {
+                if hoard.gold >= unlock.cost() {
+                    hoard.gold -= unlock.cost();
+                    hoard.unlocks.insert(unlock);
+                    hoard.save();
+                }
+            }
+            LairButtonAction::Wear(skin) => {
+                hoard.skin = skin;
+                hoard.save();
+            }
+            LairButtonAction::Back => app_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
+    play_music_event_writer.send(PlayMusicEvent::new_with_defaults("theme1.ogg"));
+}
@@ -1,7 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use animation::AnimationPlugin;
-use audio::{audio_assets_loaded, AudioPlugin, BgmChannel};
+use audio::{audio_assets_loaded, AudioPlugin};
 use bevy::{
     ecs::{event::EventUpdateSignal, query::QueryFilter},
     prelude::*,
@@ -11,25 +11,35 @@ use bevy::{
     },
 };
 use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
-use bevy_kira_audio::{AudioChannel, AudioControl};
 use camera::CameraPlugin;
+use controls::ControlsPlugin;
 use fonts::{font_assets_loaded, FontsPlugin};
 use game::GamePlugin;
 use input::InputPlugin;
+use lair::LairPlugin;
 use main_menu::MainMenuPlugin;
 use physics::PhysicsPlugin;
+use settings::SettingsPlugin;
+use species::{species_assets_loaded, SpeciesPlugin};
+use species_select::SpeciesSelectPlugin;
 use textures::{texture_assets_loaded, TexturesPlugin};
 
 mod animation;
 mod audio;
 mod camera;
+mod controls;
 #[cfg(debug_assertions)]
 mod debug;
 mod fonts;
 mod game;
 mod input;
+mod lair;
 mod main_menu;
 mod physics;
+mod settings;
+mod species;
+mod species_select;
+mod storage;
 mod textures;
 
 fn main() {
@@ -74,14 +84,24 @@ fn main() {
         FontsPlugin,
         GamePlugin,
         InputPlugin,
-        MainMenuPlugin,
         PhysicsPlugin,
+        SettingsPlugin,
+        SpeciesPlugin,
         TexturesPlugin,
     ));
 
+    // Screens outside of the game itself.
+    app.add_plugins((
+        ControlsPlugin,
+        LairPlugin,
+        MainMenuPlugin,
+        SpeciesSelectPlugin,
+    ));
+
     app.world.remove_resource::<EventUpdateSignal>();
 
     app.init_state::<AppState>();
+    app.init_state::<InGameState>();
 
     app.insert_resource(Msaa::Off);
 
@@ -92,11 +112,6 @@ fn main() {
         handle_asset_load.run_if(assets_loaded().and_then(run_once())),
     );
 
-    app.add_systems(
-        Update,
-        stop_music_on_transition.run_if(state_changed::<AppState>),
-    );
-
     app.run();
 }
 
@@ -105,10 +120,24 @@ pub enum AppState {
     #[default]
     Setup,
     MainMenu,
+    Lair,
+    Controls,
+    SpeciesSelect,
     InGame,
+    /// Passed through for a frame when a run is restarted from the pause menu.
+    Restarting,
     GameOver,
 }
 
+/// Whether the game is running or waiting on the player while in [`AppState::InGame`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, States)]
+pub enum InGameState {
+    #[default]
+    Running,
+    LevelUp,
+    Paused,
+}
+
 fn handle_asset_load(mut state: ResMut<NextState<AppState>>) {
     #[cfg(debug_assertions)]
     info!("Assets loaded successfully.");
@@ -121,16 +150,13 @@ pub fn entity_cleanup<F: QueryFilter>(mut commands: Commands, query: Query<Entit
     }
 }
 
-pub fn stop_music_on_transition(bgm_audio_channel: Res<AudioChannel<BgmChannel>>) {
-    bgm_audio_channel.stop();
-}
-
 pub fn playing() -> impl Condition<()> {
-    IntoSystem::into_system(in_state(AppState::InGame))
+    IntoSystem::into_system(in_state(AppState::InGame).and_then(in_state(InGameState::Running)))
 }
 
 fn assets_loaded() -> impl Condition<()> {
     texture_assets_loaded()
         .and_then(audio_assets_loaded())
         .and_then(font_assets_loaded())
+        .and_then(species_assets_loaded())
 }
@@ -1,9 +1,6 @@
 use bevy::{app::AppExit, prelude::*};
 
-use crate::{
-    audio::{PlayMusicEvent, PlaybackSettings},
-    entity_cleanup, AppState,
-};
+use crate::{audio::PlayMusicEvent, entity_cleanup, settings::SettingsMenuState, AppState};
 
 pub struct MainMenuPlugin;
 
@@ -35,6 +32,9 @@ struct MainMenuEntity;
 #[derive(Component)]
 enum MainMenuButtonAction {
     NewGame,
+    Lair,
+    Controls,
+    Settings,
     #[cfg(not(target_family = "wasm"))]
     Exit,
 }
@@ -74,7 +74,7 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     background_color: Color::ALICE_BLUE.into(),
                     style: Style {
                         position_type: PositionType::Absolute,
-                        bottom: Val::Percent(12.),
+                        bottom: Val::Percent(30.),
                         ..default()
                     },
                     ..default()
@@ -95,6 +95,84 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 });
             });
 
+            node.spawn((
+                ButtonBundle {
+                    background_color: Color::ALICE_BLUE.into(),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Percent(24.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MainMenuButtonAction::Lair,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle {
+                    text: Text::from_section(
+                        "Lair",
+                        TextStyle {
+                            color: Color::BLACK,
+                            font: font.clone(),
+                            font_size: 32.0,
+                        },
+                    ),
+                    ..default()
+                });
+            });
+
+            node.spawn((
+                ButtonBundle {
+                    background_color: Color::ALICE_BLUE.into(),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Percent(18.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MainMenuButtonAction::Controls,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle {
+                    text: Text::from_section(
+                        "Controls",
+                        TextStyle {
+                            color: Color::BLACK,
+                            font: font.clone(),
+                            font_size: 32.0,
+                        },
+                    ),
+                    ..default()
+                });
+            });
+
+            node.spawn((
+                ButtonBundle {
+                    background_color: Color::ALICE_BLUE.into(),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Percent(12.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MainMenuButtonAction::Settings,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle {
+                    text: Text::from_section(
+                        "Settings",
+                        TextStyle {
+                            color: Color::BLACK,
+                            font: font.clone(),
+                            font_size: 32.0,
+                        },
+                    ),
+                    ..default()
+                });
+            });
+
             #[cfg(not(target_family = "wasm"))]
             node.spawn((
                 ButtonBundle {
@@ -127,6 +205,7 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
 fn handle_main_menu_button_interactions(
     mut exit: EventWriter<AppExit>,
     mut app_state: ResMut<NextState<AppState>>,
+    mut settings_menu_state: ResMut<NextState<SettingsMenuState>>,
     query: Query<(&Interaction, &MainMenuButtonAction), With<Button>>,
 ) {
     for (interaction, main_menu_button_action) in query.iter() {
@@ -137,7 +216,16 @@ fn handle_main_menu_button_interactions(
                     exit.send(AppExit);
                 }
                 MainMenuButtonAction::NewGame => {
-                    app_state.set(AppState::InGame);
+                    app_state.set(AppState::SpeciesSelect);
+                }
+                MainMenuButtonAction::Lair => {
+                    app_state.set(AppState::Lair);
+                }
+                MainMenuButtonAction::Controls => {
+                    app_state.set(AppState::Controls);
+                }
+                MainMenuButtonAction::Settings => {
+                    settings_menu_state.set(SettingsMenuState::Open);
                 }
             },
             Interaction::Hovered => (),
@@ -147,12 +235,5 @@ fn handle_main_menu_button_interactions(
 }
 
 fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
-    play_music_event_writer.send(PlayMusicEvent::new(
-        "theme1.ogg",
-        Some(PlaybackSettings {
-            volume: 0.25,
-            ..default()
-        }),
-        None,
-    ));
+    play_music_event_writer.send(PlayMusicEvent::new_with_defaults("theme1.ogg"));
 }
@@ -0,0 +1,436 @@
+use bevy::{
+    prelude::*,
+    ui::FocusPolicy,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::{AudioBus, AudioMixer},
+    entity_cleanup,
+    input::{Action, ActionState, ActionSystem},
+    storage,
+};
+
+const SETTINGS_KEY: &str = "settings";
+
+const RESOLUTIONS: [(f32, f32); 4] = [(1280., 720.), (1600., 900.), (1920., 1080.), (2560., 1440.)];
+const UI_SCALES: [f32; 4] = [0.75, 1., 1.25, 1.5];
+const VOLUME_STEP: f64 = 0.05;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Settings>(SETTINGS_KEY).unwrap_or_default());
+
+        app.init_state::<SettingsMenuState>();
+
+        app.add_systems(Update, apply_settings.run_if(resource_changed::<Settings>));
+
+        app.add_systems(
+            PreUpdate,
+            close_settings_menu
+                .after(ActionSystem)
+                .run_if(in_state(SettingsMenuState::Open)),
+        );
+
+        app.add_systems(OnEnter(SettingsMenuState::Open), setup_settings_menu);
+
+        // Rebuild the menu whenever a setting changes.
+        app.add_systems(
+            Update,
+            (
+                handle_settings_button_interactions,
+                (entity_cleanup::<With<SettingsEntity>>, setup_settings_menu)
+                    .chain()
+                    .run_if(resource_changed::<Settings>),
+            )
+                .chain()
+                .run_if(in_state(SettingsMenuState::Open)),
+        );
+
+        app.add_systems(
+            OnExit(SettingsMenuState::Open),
+            entity_cleanup::<With<SettingsEntity>>,
+        );
+    }
+}
+
+/// Whether the settings menu is shown on top of the current screen. It is opened from the
+/// main menu and the pause menu.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, States)]
+pub enum SettingsMenuState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Options chosen by the player, kept between sessions.
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f64,
+    pub music_volume: f64,
+    pub sfx_volume: f64,
+    pub ui_volume: f64,
+    pub ambience_volume: f64,
+    pub fullscreen: bool,
+    pub resolution: (f32, f32),
+    pub ui_scale: f32,
+    pub difficulty: Difficulty,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 0.25,
+            sfx_volume: 1.,
+            ui_volume: 1.,
+            ambience_volume: 1.,
+            fullscreen: false,
+            resolution: RESOLUTIONS[0],
+            ui_scale: 1.,
+            difficulty: Difficulty::Normal,
+        }
+    }
+}
+
+impl Settings {
+    pub fn save(&self) {
+        storage::save(SETTINGS_KEY, self);
+    }
+
+    fn change(&mut self, setting: Setting, forward: bool) {
+        let volume_step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+        let step_volume =
+            |volume: f64| ((volume + volume_step) / VOLUME_STEP).round() * VOLUME_STEP;
+
+        match setting {
+            Setting::MasterVolume => {
+                self.master_volume = step_volume(self.master_volume).clamp(0., 1.)
+            }
+            Setting::MusicVolume => {
+                self.music_volume = step_volume(self.music_volume).clamp(0., 1.)
+            }
+            Setting::SfxVolume => self.sfx_volume = step_volume(self.sfx_volume).clamp(0., 1.),
+            Setting::UiVolume => self.ui_volume = step_volume(self.ui_volume).clamp(0., 1.),
+            Setting::AmbienceVolume => {
+                self.ambience_volume = step_volume(self.ambience_volume).clamp(0., 1.)
+            }
+            Setting::Fullscreen => self.fullscreen = !self.fullscreen,
+            Setting::Resolution => self.resolution = cycle(&RESOLUTIONS, self.resolution, forward),
+            Setting::UiScale => self.ui_scale = cycle(&UI_SCALES, self.ui_scale, forward),
+            Setting::Difficulty => {
+                self.difficulty = cycle(&Difficulty::ALL, self.difficulty, forward)
+            }
+        }
+    }
+
+    fn value(&self, setting: Setting) -> String {
+        let percent = |volume: f64| format!("{:.0}%", volume * 100.);
+
+        match setting {
+            Setting::MasterVolume => percent(self.master_volume),
+            Setting::MusicVolume => percent(self.music_volume),
+            Setting::SfxVolume => percent(self.sfx_volume),
+            Setting::UiVolume => percent(self.ui_volume),
+            Setting::AmbienceVolume => percent(self.ambience_volume),
+            Setting::Fullscreen => if self.fullscreen { "On" } else { "Off" }.to_string(),
+            Setting::Resolution => format!("{} x {}", self.resolution.0, self.resolution.1),
+            Setting::UiScale => format!("{:.0}%", self.ui_scale * 100.),
+            Setting::Difficulty => self.difficulty.name().to_string(),
+        }
+    }
+}
+
+/// Returns the option before or after `current`, wrapping around. Values that aren't in
+/// `options` start over from the first one.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, forward: bool) -> T {
+    let Some(index) = options.iter().position(|&option| option == current) else {
+        return options[0];
+    };
+    let index = if forward {
+        (index + 1) % options.len()
+    } else {
+        (index + options.len() - 1) % options.len()
+    };
+
+    options[index]
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+
+    /// Multiplier on the damage dealt by enemies.
+    pub fn enemy_damage(&self) -> f32 {
+        match self {
+            Self::Easy => 0.5,
+            Self::Normal => 1.,
+            Self::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier on how quickly new enemies arrive.
+    pub fn spawn_rate(&self) -> f32 {
+        match self {
+            Self::Easy => 0.75,
+            Self::Normal => 1.,
+            Self::Hard => 1.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Setting {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    UiVolume,
+    AmbienceVolume,
+    Fullscreen,
+    Resolution,
+    UiScale,
+    Difficulty,
+}
+
+impl Setting {
+    const ALL: [Self; 9] = [
+        Self::MasterVolume,
+        Self::MusicVolume,
+        Self::SfxVolume,
+        Self::UiVolume,
+        Self::AmbienceVolume,
+        Self::Fullscreen,
+        Self::Resolution,
+        Self::UiScale,
+        Self::Difficulty,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::MasterVolume => "Master Volume",
+            Self::MusicVolume => "Music Volume",
+            Self::SfxVolume => "Effects Volume",
+            Self::UiVolume => "Interface Volume",
+            Self::AmbienceVolume => "Ambience Volume",
+            Self::Fullscreen => "Fullscreen",
+            Self::Resolution => "Resolution",
+            Self::UiScale => "Interface Scale",
+            Self::Difficulty => "Difficulty",
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsEntity;
+
+#[derive(Component)]
+enum SettingsButtonAction {
+    Previous(Setting),
+    Next(Setting),
+    Close,
+}
+
+/// The video settings last applied to the window, so that changing any other setting leaves a
+/// window the player resized by hand alone.
+#[derive(Default)]
+struct AppliedVideoSettings {
+    fullscreen: Option<bool>,
+    #[cfg(not(target_family = "wasm"))]
+    resolution: Option<(f32, f32)>,
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+    mut audio_mixer: ResMut<AudioMixer>,
+    mut applied_video_settings: Local<AppliedVideoSettings>,
+) {
+    audio_mixer.set_volume(AudioBus::Master, settings.master_volume);
+    audio_mixer.set_volume(AudioBus::Music, settings.music_volume);
+    audio_mixer.set_volume(AudioBus::Sfx, settings.sfx_volume);
+    audio_mixer.set_volume(AudioBus::Ui, settings.ui_volume);
+    audio_mixer.set_volume(AudioBus::Ambience, settings.ambience_volume);
+
+    ui_scale.0 = settings.ui_scale;
+
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    if applied_video_settings.fullscreen != Some(settings.fullscreen) {
+        applied_video_settings.fullscreen = Some(settings.fullscreen);
+        window.mode = if settings.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        };
+    }
+
+    // The canvas is sized by the page on the web.
+    #[cfg(not(target_family = "wasm"))]
+    if applied_video_settings.resolution != Some(settings.resolution) {
+        applied_video_settings.resolution = Some(settings.resolution);
+        window
+            .resolution
+            .set(settings.resolution.0, settings.resolution.1);
+    }
+}
+
+fn setup_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+    let text_style = |color: Color, font_size: f32| TextStyle {
+        color,
+        font: font.clone(),
+        font_size,
+    };
+    let spawn_button = |builder: &mut ChildBuilder, label: &str, action: SettingsButtonAction| {
+        builder
+            .spawn((
+                ButtonBundle {
+                    background_color: Color::ALICE_BLUE.into(),
+                    style: Style {
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::horizontal(Val::Px(8.)),
+                        min_width: Val::Px(40.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                action,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    label,
+                    text_style(Color::BLACK, 32.),
+                ));
+            });
+    };
+
+    commands
+        .spawn((
+            SettingsEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::rgb(0.05, 0.05, 0.1).with_a(0.95)),
+                focus_policy: FocusPolicy::Block,
+                style: Style {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    row_gap: Val::Px(12.),
+                    width: Val::Percent(100.),
+                    ..default()
+                },
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+        ))
+        .with_children(|node| {
+            node.spawn(TextBundle::from_section(
+                "Settings",
+                text_style(Color::WHITE, 64.),
+            ));
+
+            for setting in Setting::ALL {
+                node.spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(TextBundle {
+                        style: Style {
+                            width: Val::Px(240.),
+                            ..default()
+                        },
+                        text: Text::from_section(setting.name(), text_style(Color::WHITE, 28.)),
+                        ..default()
+                    });
+
+                    spawn_button(row, "<", SettingsButtonAction::Previous(setting));
+
+                    row.spawn(NodeBundle {
+                        style: Style {
+                            justify_content: JustifyContent::Center,
+                            width: Val::Px(200.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|value| {
+                        value.spawn(TextBundle::from_section(
+                            settings.value(setting),
+                            text_style(Color::GOLD, 28.),
+                        ));
+                    });
+
+                    spawn_button(row, ">", SettingsButtonAction::Next(setting));
+                });
+            }
+
+            spawn_button(node, "Back", SettingsButtonAction::Close);
+        });
+}
+
+fn handle_settings_button_interactions(
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<SettingsMenuState>>,
+    query: Query<(&Interaction, &SettingsButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, settings_button_action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *settings_button_action {
+            SettingsButtonAction::Previous(setting) => {
+                settings.change(setting, false);
+                settings.save();
+            }
+            SettingsButtonAction::Next(setting) => {
+                settings.change(setting, true);
+                settings.save();
+            }
+            SettingsButtonAction::Close => next_state.set(SettingsMenuState::Closed),
+        }
+    }
+}
+
+fn close_settings_menu(
+    action_state: Res<ActionState>,
+    mut next_state: ResMut<NextState<SettingsMenuState>>,
+) {
+    if action_state.just_pressed(Action::Pause) {
+        next_state.set(SettingsMenuState::Closed);
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadedFolder,
+        RecursiveDependencyLoadState,
+    },
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::game::BreathElement;
+
+pub const ASSET_FOLDER_SPECIES: &str = "species";
+
+pub struct SpeciesPlugin;
+
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DragonSpecies>();
+        app.register_asset_loader(DragonSpeciesLoader);
+        app.init_resource::<SpeciesLoadState>();
+        app.init_resource::<SpeciesHandles>();
+        app.add_systems(Startup, load_species);
+        app.add_systems(
+            Update,
+            update_species_assets_load_state.run_if(not(resource_equals(SpeciesLoadState::Loaded))),
+        );
+    }
+}
+
+/// A playable kind of dragon, loaded from a `.dragon.ron` file in the species folder.
+#[derive(Asset, TypePath, Deserialize)]
+pub struct DragonSpecies {
+    pub name: String,
+    pub description: String,
+    /// Path of the sprite sheet, relative to the assets folder.
+    pub texture: String,
+    pub frame_size: Vec2,
+    pub columns: usize,
+    /// First and last frame of the flight animation.
+    pub flight_frames: (usize, usize),
+    /// Frame held while the dragon banks hard into a turn, drawn leaning right. It must not be
+    /// part of the flight animation, or banking can't be told apart from flapping.
+    pub banking_frame: usize,
+    /// Size of the sprite relative to its frame size.
+    pub scale: f32,
+    /// Half extents of the hitbox.
+    pub hitbox: Vec2,
+    pub health: i16,
+    pub fire: i16,
+    pub speed: f32,
+    pub breath: BreathElement,
+}
+
+/// The species picked for the next run.
+#[derive(Resource)]
+pub struct SelectedSpecies(pub AssetId<DragonSpecies>);
+
+struct DragonSpeciesLoader;
+
+impl AssetLoader for DragonSpeciesLoader {
+    type Asset = DragonSpecies;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<DragonSpecies, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dragon.ron"]
+    }
+}
+
+#[derive(Resource, Default, PartialEq)]
+enum SpeciesLoadState {
+    #[default]
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+impl From<RecursiveDependencyLoadState> for SpeciesLoadState {
+    fn from(value: RecursiveDependencyLoadState) -> Self {
+        match value {
+            RecursiveDependencyLoadState::NotLoaded => Self::NotLoaded,
+            RecursiveDependencyLoadState::Loading => Self::Loading,
+            RecursiveDependencyLoadState::Loaded => Self::Loaded,
+            RecursiveDependencyLoadState::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct SpeciesHandles(
+    #[cfg(not(target_family = "wasm"))] Handle<LoadedFolder>,
+    #[cfg(target_family = "wasm")] Vec<Handle<DragonSpecies>>,
+);
+
+fn load_species(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let species_handles = {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            asset_server.load_folder(ASSET_FOLDER_SPECIES)
+        }
+
+        #[cfg(target_family = "wasm")]
+        {
+            let asset_species_list = [
+                format!("{ASSET_FOLDER_SPECIES}/elder.dragon.ron"),
+                format!("{ASSET_FOLDER_SPECIES}/ice.dragon.ron"),
+                format!("{ASSET_FOLDER_SPECIES}/red.dragon.ron"),
+                format!("{ASSET_FOLDER_SPECIES}/wyvern.dragon.ron"),
+            ];
+            asset_species_list
+                .iter()
+                .map(|path| asset_server.load::<DragonSpecies>(path))
+                .collect::<Vec<Handle<DragonSpecies>>>()
+        }
+    };
+
+    commands.insert_resource(SpeciesHandles(species_handles));
+}
+
+fn update_species_assets_load_state(
+    mut species_load_state: ResMut<SpeciesLoadState>,
+    species_handles: Res<SpeciesHandles>,
+    asset_server: Res<AssetServer>,
+) {
+    *species_load_state = {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            asset_server
+                .recursive_dependency_load_state(species_handles.id())
+                .into()
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            let all_loaded = species_handles.iter().all(|handle| {
+                asset_server.recursive_dependency_load_state(handle.id())
+                    == RecursiveDependencyLoadState::Loaded
+            });
+            if all_loaded {
+                RecursiveDependencyLoadState::Loaded.into()
+            } else {
+                RecursiveDependencyLoadState::NotLoaded.into()
+            }
+        }
+    };
+}
+
+pub fn species_assets_loaded() -> impl Condition<()> {
+    IntoSystem::into_system(resource_equals(SpeciesLoadState::Loaded))
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+use crate::{
+    audio::PlayMusicEvent,
+    entity_cleanup,
+    species::{DragonSpecies, SelectedSpecies},
+    AppState,
+};
+
+pub struct SpeciesSelectPlugin;
+
+impl Plugin for SpeciesSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::SpeciesSelect),
+            (setup_species_select, play_background_music),
+        );
+
+        app.add_systems(
+            Update,
+            handle_species_select_button_interactions.run_if(in_state(AppState::SpeciesSelect)),
+        );
+
+        app.add_systems(
+            OnExit(AppState::SpeciesSelect),
+            entity_cleanup::<With<SpeciesSelectEntity>>,
+        );
+    }
+}
+
+#[derive(Component)]
+struct SpeciesSelectEntity;
+
+#[derive(Component)]
+enum SpeciesSelectButtonAction {
+    Select(AssetId<DragonSpecies>),
+    Back,
+}
+
+fn setup_species_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    species_assets: Res<Assets<DragonSpecies>>,
+) {
+    let font = asset_server
+        .get_handle("fonts/MorrisRomanAlternate-Black.ttf")
+        .unwrap_or_default();
+    let mut species = species_assets.iter().collect::<Vec<_>>();
+
+    species.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    commands
+        .spawn((
+            SpeciesSelectEntity,
+            NodeBundle {
+                background_color: BackgroundColor(Color::rgb(0.05, 0.05, 0.1)),
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(12.),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|node| {
+            node.spawn(TextBundle::from_section(
+                "Choose your dragon",
+                TextStyle {
+                    color: Color::WHITE,
+                    font: font.clone(),
+                    font_size: 64.,
+                },
+            ));
+
+            for (id, species) in species {
+                node.spawn((
+                    ButtonBundle {
+                        background_color: Color::ALICE_BLUE.into(),
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            flex_direction: FlexDirection::Column,
+                            padding: UiRect::all(Val::Px(8.)),
+                            width: Val::Px(480.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    SpeciesSelectButtonAction::Select(id),
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        species.name.clone(),
+                        TextStyle {
+                            color: Color::BLACK,
+                            font: font.clone(),
+                            font_size: 32.,
+                        },
+                    ));
+                    button.spawn(TextBundle::from_section(
+                        format!(
+                            "Health {} - Fire {} - Speed {}",
+                            species.health, species.fire, species.speed
+                        ),
+                        TextStyle {
+                            color: Color::DARK_GRAY,
+                            font: font.clone(),
+                            font_size: 20.,
+                        },
+                    ));
+                    button.spawn(TextBundle::from_section(
+                        species.description.clone(),
+                        TextStyle {
+                            color: Color::DARK_GRAY,
+                            font: font.clone(),
+                            font_size: 20.,
+                        },
+                    ));
+                });
+            }
+
+            node.spawn((
+                ButtonBundle {
+                    background_color: Color::ALICE_BLUE.into(),
+                    style: Style {
+                        padding: UiRect::horizontal(Val::Px(8.)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                SpeciesSelectButtonAction::Back,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    "Back to Menu",
+                    TextStyle {
+                        color: Color::BLACK,
+                        font: font.clone(),
+                        font_size: 32.,
+                    },
+                ));
+            });
+        });
+}
+
+fn handle_species_select_button_interactions(
+    mut commands: Commands,
+    mut app_state: ResMut<NextState<AppState>>,
+    query: Query<(&Interaction, &SpeciesSelectButtonAction), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, species_select_button_action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *species_select_button_action {
+            SpeciesSelectButtonAction::Select(id) => {
+                commands.insert_resource(SelectedSpecies(id));
+                app_state.set(AppState::InGame);
+            }
+            SpeciesSelectButtonAction::Back => app_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+fn play_background_music(mut play_music_event_writer: EventWriter<PlayMusicEvent>) {
+    play_music_event_writer.send(PlayMusicEvent::new_with_defaults("theme1.ogg"));
+}
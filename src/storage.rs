@@ -0,0 +1,71 @@
+use bevy::log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Reads the value saved under `key`, if there is one and it can be parsed.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let contents = read(key)?;
+
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Couldn't parse saved {key}: {error}");
+            None
+        }
+    }
+}
+
+/// Saves `value` under `key`, replacing whatever was saved before.
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::to_string(value) {
+        Ok(contents) => write(key, &contents),
+        Err(error) => warn!("Couldn't serialize {key}: {error}"),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn path(key: &str) -> std::path::PathBuf {
+    std::path::Path::new("saves").join(format!("{key}.ron"))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(key: &str, contents: &str) {
+    let path = path(key);
+
+    if let Some(directory) = path.parent() {
+        if let Err(error) = std::fs::create_dir_all(directory) {
+            warn!("Couldn't create {}: {error}", directory.display());
+            return;
+        }
+    }
+
+    if let Err(error) = std::fs::write(&path, contents) {
+        warn!("Couldn't save {}: {error}", path.display());
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(target_family = "wasm")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+#[cfg(target_family = "wasm")]
+fn write(key: &str, contents: &str) {
+    let Some(local_storage) = local_storage() else {
+        warn!("Local storage isn't available, {key} won't be saved");
+        return;
+    };
+
+    if local_storage.set_item(key, contents).is_err() {
+        warn!("Couldn't save {key} to local storage");
+    }
+}
@@ -62,6 +62,9 @@ fn load_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
         {
             let asset_textures_list = [
                 format!("{ASSET_FOLDER_TEXTURES}/dragon.png"),
+                format!("{ASSET_FOLDER_TEXTURES}/dragon_elder.png"),
+                format!("{ASSET_FOLDER_TEXTURES}/dragon_ice.png"),
+                format!("{ASSET_FOLDER_TEXTURES}/dragon_wyvern.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/enemy_archer.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/enemy_axe.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/fire_anim_washed.png"),
@@ -69,6 +72,7 @@ fn load_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
                 format!("{ASSET_FOLDER_TEXTURES}/fire_strech.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/fire_wide.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/menu_background.png"),
+                format!("{ASSET_FOLDER_TEXTURES}/projectiles.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/scale_anim.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/tileset_ground.png"),
                 format!("{ASSET_FOLDER_TEXTURES}/tileset_objects.png"),